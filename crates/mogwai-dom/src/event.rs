@@ -63,6 +63,40 @@ impl JsDomEvent {
     pub fn clone_as<T: JsCast + Clone>(&self) -> Option<T> {
         self.inner.dyn_ref::<T>().cloned()
     }
+
+    /// Borrow the event as `T`, eg. a `web_sys::KeyboardEvent`, without
+    /// cloning it.
+    pub fn downcast_ref<T: JsCast>(&self) -> Option<&T> {
+        self.inner.dyn_ref::<T>()
+    }
+
+    /// The pointer's position in the viewport, if this is a mouse or pointer
+    /// event.
+    pub fn pointer_position(&self) -> Option<(f64, f64)> {
+        let ev = self.inner.dyn_ref::<web_sys::MouseEvent>()?;
+        Some((ev.client_x() as f64, ev.client_y() as f64))
+    }
+
+    /// The `value` of this event's target, if it has one.
+    ///
+    /// This is the current text of an `input` or `textarea`, or the selected
+    /// value of a `select`.
+    pub fn target_value(&self) -> Option<String> {
+        let target = self.inner.dyn_ref::<web_sys::Event>()?.target()?;
+        js_sys::Reflect::get(&target, &JsValue::from_str("value"))
+            .ok()?
+            .as_string()
+    }
+
+    /// The boolean `property` of this event's target, if it has one.
+    ///
+    /// Eg. `target_bool("checked")` on the `change` event of a checkbox.
+    pub fn target_bool(&self, property: &str) -> Option<bool> {
+        let target = self.inner.dyn_ref::<web_sys::Event>()?.target()?;
+        js_sys::Reflect::get(&target, &JsValue::from_str(property))
+            .ok()?
+            .as_bool()
+    }
 }
 
 pub(crate) struct WebCallback {
@@ -90,8 +124,7 @@ pub(crate) fn add_event(
     target: &web_sys::EventTarget,
     tx: Pin<Box<dyn Sink<JsDomEvent> + Send + Sync + 'static>>,
 ) -> WebCallback {
-    let closure = Closure::wrap(Box::new(move |val: JsValue| {
-        let ev: web_sys::Event = val.unchecked_into();
+    add_event_callback(ev_name, target, move |ev: web_sys::Event| {
         let js_dom_event = JsDomEvent::from(&ev);
         match tx.try_send(js_dom_event) {
             Ok(()) => {}
@@ -103,6 +136,21 @@ pub(crate) fn add_event(
                 log::error!("channel for event {:?} is full", ev);
             }
         }
+    })
+}
+
+/// Add an event listener of the given name to the given target, calling `f`
+/// synchronously with each event.
+///
+/// This is for handlers that must act before the event finishes dispatching,
+/// eg. to prevent its default action.
+pub(crate) fn add_event_callback(
+    ev_name: &'static str,
+    target: &web_sys::EventTarget,
+    mut f: impl FnMut(web_sys::Event) + 'static,
+) -> WebCallback {
+    let closure = Closure::wrap(Box::new(move |val: JsValue| {
+        f(val.unchecked_into());
     }) as Box<dyn FnMut(JsValue)>);
 
     target
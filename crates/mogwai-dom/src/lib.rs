@@ -38,8 +38,10 @@
 //! specific Javascript types.
 pub mod an_introduction;
 pub mod event;
+pub mod testing;
 pub mod utils;
 pub mod view;
+pub mod web;
 pub use mogwai_macros::{builder, html, rsx};
 
 pub mod core {
@@ -330,6 +332,21 @@ mod nonwasm {
         }
     }
 
+    #[test]
+    fn ssr_run_while_timeout_expires() {
+        futures_lite::future::block_on(async {
+            let view = SsrDom::try_from(rsx! { div() {} }).unwrap();
+            let err = view
+                .run_while_timeout(10, futures_lite::future::pending::<()>())
+                .await
+                .unwrap_err();
+            assert_eq!(err.to_string(), "future did not finish within 10ms");
+
+            let n = view.run_while_timeout(1000, async { 3 }).await.unwrap();
+            assert_eq!(n, 3);
+        });
+    }
+
     #[test]
     pub fn ssr_simple_update() {
         futures_lite::future::block_on(async {
@@ -348,6 +365,49 @@ mod nonwasm {
         });
     }
 
+    #[test]
+    fn ssr_wait_for_steady_state() {
+        futures_lite::future::block_on(async {
+            let mut text = Input::<String>::default();
+            let mut class = Input::<String>::default();
+            let view = SsrDom::try_from(rsx! {
+                p(class = ("p_class", class.stream().unwrap())) {
+                    {("hello", text.stream().unwrap())}
+                }
+            })
+            .unwrap();
+            text.set("goodbye").await.unwrap();
+            class.set("updated").await.unwrap();
+            view.wait_for_steady_state().await;
+            assert_eq!(
+                view.html_string().await,
+                r#"<p class="updated">goodbye</p>"#
+            );
+        });
+    }
+
+    #[test]
+    pub fn ssr_formatted_text_update() {
+        futures_lite::future::block_on(async {
+            let mut count = Input::<u32>::default();
+
+            let view = SsrDom::try_from(ViewBuilder::text("").with_formatted_text(
+                0,
+                count.stream().unwrap(),
+                |n| format!("Count: {}", n),
+            ))
+            .unwrap();
+            let v = view.clone();
+            view.run_while(async move {
+                wait_eq(r#"Count: 0"#, 1.0, &v).await;
+                count.set(1u32).await.unwrap();
+                wait_eq(r#"Count: 1"#, 1.0, &v).await;
+            })
+            .await
+            .unwrap();
+        });
+    }
+
     #[test]
     pub fn ssr_simple_nested_update() {
         futures_lite::future::block_on(async {
@@ -456,6 +516,375 @@ mod nonwasm {
         });
     }
 
+    #[test]
+    fn ssr_tabindex() {
+        futures_lite::future::block_on(async {
+            let view = SsrDom::try_from(rsx! {
+                div(tabindex = -1) {
+                    button(tabindex = "0") {"Submit"}
+                }
+            })
+            .unwrap();
+            assert_eq!(
+                view.html_string().await,
+                r#"<div tabindex="-1"><button tabindex="0">Submit</button></div>"#
+            );
+
+            let view = SsrDom::try_from(ViewBuilder::element("p").with_tabindex(2)).unwrap();
+            assert_eq!(view.html_string().await, r#"<p tabindex="2"></p>"#);
+
+            let index = 3.to_string();
+            let (tx, rx) = mpsc::bounded::<String>(1);
+            let view = SsrDom::try_from(rsx! {
+                div(tabindex = index) {
+                    a(tabindex = format!("{}", 4)) {}
+                    button(tabindex = ("5", rx)) {}
+                }
+            })
+            .unwrap();
+            assert_eq!(
+                view.html_string().await,
+                r#"<div tabindex="3"><a tabindex="4"></a> <button tabindex="5"></button></div>"#
+            );
+            drop(tx);
+        });
+    }
+
+    #[test]
+    fn rsx_namespace_propagates_to_children() {
+        use mogwai::view::ViewIdentity;
+
+        let svg_ns = "http://www.w3.org/2000/svg";
+        let is_svg = |builder: &ViewBuilder| {
+            matches!(&builder.identity, ViewIdentity::NamespacedBranch(_, ns) if ns == svg_ns)
+        };
+        let first_child = |builder: ViewBuilder| {
+            builder
+                .initial_values
+                .into_iter()
+                .find_map(|update| match update {
+                    mogwai::view::Update::Child(ListPatch::Push(child)) => Some(child),
+                    _ => None,
+                })
+                .unwrap()
+        };
+
+        let shorthand = rsx! {
+            svg(xmlns:svg, width = "100") {
+                circle(r = "40") {}
+            }
+        };
+        assert!(is_svg(&shorthand));
+        assert!(is_svg(&first_child(shorthand)));
+
+        let pseudo = rsx! {
+            svg(namespace = svg_ns) {
+                g() {
+                    circle(r = "40") {}
+                }
+            }
+        };
+        assert!(is_svg(&pseudo));
+        let g = first_child(pseudo);
+        assert!(is_svg(&g));
+        assert!(is_svg(&first_child(g)));
+
+        // the explicit xmlns attribute still works, and children may override
+        // the inherited namespace
+        let explicit = rsx! {
+            svg(xmlns = svg_ns) {
+                foreignObject() {
+                    div(namespace = "http://www.w3.org/1999/xhtml") {}
+                }
+            }
+        };
+        assert!(is_svg(&explicit));
+        let foreign = first_child(explicit);
+        assert!(is_svg(&foreign));
+        assert!(!is_svg(&first_child(foreign)));
+
+        futures_lite::future::block_on(async {
+            let view = SsrDom::try_from(html! {
+                <svg xmlns:svg width="100"><circle r="40" /></svg>
+            })
+            .unwrap();
+            assert_eq!(
+                view.html_string().await,
+                r#"<svg xmlns="http://www.w3.org/2000/svg" width="100"><circle xmlns="http://www.w3.org/2000/svg" r="40"></circle></svg>"#
+            );
+        });
+    }
+
+    #[test]
+    fn query_selector_is_empty_off_wasm() {
+        let dom = JsDom::from(wasm_bindgen::JsValue::NULL);
+        assert!(dom.query_selector("li").is_none());
+        assert!(dom.query_selector_all("li").is_empty());
+    }
+
+    #[test]
+    fn animation_frames_tick_off_wasm() {
+        let frames =
+            futures_lite::future::block_on(JsDom::animation_frames().take(3).collect::<Vec<_>>());
+        assert_eq!(frames.len(), 3);
+        assert!(frames.windows(2).all(|ts| ts[0] < ts[1]), "{:?}", frames);
+    }
+
+    #[test]
+    fn ssr_dispatch_event_drives_logic() {
+        let clicks = mogwai::relay::Output::<()>::default();
+        let (tx_label, rx_label) = mpsc::bounded::<String>(1);
+        let builder = rsx! {
+            div() {
+                button(id = "inc", on:click = clicks.sink().contra_map(|_: DomEvent| ())) {
+                    {("clicked 0 times", rx_label)}
+                }
+            }
+        }
+        .with_task(async move {
+            let mut n = 0;
+            while let Some(()) = clicks.get().await {
+                n += 1;
+                tx_label.send(format!("clicked {} times", n)).await.unwrap();
+            }
+        });
+        let view = SsrDom::try_from(builder).unwrap();
+        futures_lite::future::block_on(async {
+            // let the logic loop start waiting on clicks
+            view.wait_for_steady_state().await;
+            for _ in 0..2 {
+                view.dispatch_event("button#inc", "click", SsrDomEvent(Value::Null))
+                    .unwrap();
+                view.wait_for_steady_state().await;
+            }
+            assert_eq!(
+                view.html_string().await,
+                r#"<div><button id="inc">clicked 2 times</button></div>"#
+            );
+            assert!(view
+                .dispatch_event("#missing", "click", SsrDomEvent(Value::Null))
+                .is_err());
+        });
+    }
+
+    #[test]
+    fn aborted_task_stops_running() {
+        let count = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let (builder, handle) = ViewBuilder::element("div").with_task_handle({
+            let count = count.clone();
+            async move {
+                loop {
+                    count.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                    mogwai::time::wait_millis(1).await;
+                }
+            }
+        });
+        let ssr = SsrDom::try_from(builder).unwrap();
+        futures_lite::future::block_on(ssr.executor.run(async {
+            mogwai::time::wait_millis(20).await;
+            assert!(count.load(std::sync::atomic::Ordering::SeqCst) > 0);
+            handle.abort();
+            mogwai::time::wait_millis(5).await;
+            let stopped_at = count.load(std::sync::atomic::Ordering::SeqCst);
+            mogwai::time::wait_millis(20).await;
+            assert_eq!(count.load(std::sync::atomic::Ordering::SeqCst), stopped_at);
+        }));
+        assert!(handle.is_aborted());
+    }
+
+    #[test]
+    fn ssr_modify_attrib_and_style() {
+        futures_lite::future::block_on(async {
+            let view = SsrDom::try_from(rsx! { a(class = "nav-link") {} }).unwrap();
+            let add_active = |class: Option<&String>| {
+                Some(match class {
+                    Some(class) => format!("{} active", class),
+                    None => "active".to_string(),
+                })
+            };
+            view.update(Update::Attribute(HashPatch::modify(
+                "class".to_string(),
+                add_active,
+            )))
+            .unwrap();
+            view.update(Update::Attribute(HashPatch::modify(
+                "title".to_string(),
+                add_active,
+            )))
+            .unwrap();
+            view.update(Update::Style(HashPatch::modify(
+                "color".to_string(),
+                |color: Option<&String>| Some(color.map_or("red", |_| "blue").to_string()),
+            )))
+            .unwrap();
+            assert_eq!(
+                view.html_string().await,
+                r#"<a class="nav-link active" title="active" style="color: red;"></a>"#
+            );
+        });
+    }
+
+    #[test]
+    fn rsx_if_else_nodes() {
+        futures_lite::future::block_on(async {
+            let view = |logged_in: bool, name: Option<&str>| {
+                SsrDom::try_from(rsx! {
+                    nav() {
+                        {if logged_in {
+                            a(href = "#/logout") {"Log out"}
+                        } else {
+                            a(href = "#/login") {"Log in"}
+                        }}
+                        {if let Some(name) = name {
+                            p() {{format!("Hi {}", name)}}
+                        } else if logged_in {
+                            p() {"Hi"}
+                        }}
+                    }
+                })
+                .unwrap()
+            };
+            assert_eq!(
+                view(true, Some("ann")).html_string().await,
+                r##"<nav><a href="#/logout">Log out</a> <p>Hi ann</p></nav>"##
+            );
+            assert_eq!(
+                view(true, None).html_string().await,
+                r##"<nav><a href="#/logout">Log out</a> <p>Hi</p></nav>"##
+            );
+            assert_eq!(
+                view(false, None).html_string().await,
+                r##"<nav><a href="#/login">Log in</a></nav>"##
+            );
+
+            let missing_else: Option<ViewBuilder> = rsx! {
+                {if false {
+                    p() {"never"}
+                }}
+            };
+            assert!(missing_else.is_none());
+        });
+    }
+
+    #[test]
+    fn rsx_attribute_spread() {
+        futures_lite::future::block_on(async {
+            let extra_attrs = vec![
+                ("role".to_string(), "note".to_string()),
+                ("title".to_string(), "overridden".to_string()),
+            ];
+            let dom = SsrDom::try_from(rsx! {
+                p(..extra_attrs, id = "starwars", title = "original") {"Hello"}
+            })
+            .unwrap();
+            let html = dom.html_string().await;
+            assert!(html.contains(r#"id="starwars""#), "{}", html);
+            assert!(html.contains(r#"role="note""#), "{}", html);
+            assert!(html.contains(r#"title="overridden""#), "{}", html);
+            assert!(!html.contains("original"), "{}", html);
+        });
+    }
+
+    #[test]
+    fn rsx_if_expressions_stay_blocks() {
+        fn card(n: u32) -> ViewBuilder {
+            rsx! { p() {{format!("card {}", n)}} }
+        }
+
+        futures_lite::future::block_on(async {
+            let flag = true;
+            let view = SsrDom::try_from(rsx! {
+                div() {
+                    {if flag { card(1) } else { card(2) }}
+                    {if flag {
+                        let n = 3;
+                        card(n)
+                    } else {
+                        card(4)
+                    }}
+                }
+            })
+            .unwrap();
+            assert_eq!(
+                view.html_string().await,
+                "<div><p>card 1</p> <p>card 3</p></div>"
+            );
+        });
+    }
+
+    #[test]
+    fn ssr_with_key() {
+        futures_lite::future::block_on(async {
+            let view = SsrDom::try_from(
+                ViewBuilder::element("ul")
+                    .append(rsx! { li() {"A"} }.with_key("a"))
+                    .append(rsx! { li() {"B"} }.with_key("b")),
+            )
+            .unwrap();
+            assert_eq!(
+                view.html_string().await,
+                r#"<ul><li data-mogwai-key="a">A</li> <li data-mogwai-key="b">B</li></ul>"#
+            );
+        });
+    }
+
+    #[test]
+    fn ssr_append_iter() {
+        futures_lite::future::block_on(async {
+            let view = SsrDom::try_from(ViewBuilder::element("ol").append_iter(
+                (0..3).map(|i| ViewBuilder::element("li").append(ViewBuilder::text(i.to_string()))),
+            ))
+            .unwrap();
+            assert_eq!(
+                view.html_string().await,
+                "<ol><li>0</li> <li>1</li> <li>2</li></ol>"
+            );
+        });
+    }
+
+    #[test]
+    fn ssr_aria_describedby() {
+        futures_lite::future::block_on(async {
+            let (input, help) = rsx! { input(type = "password") {} }
+                .with_aria_describedby(rsx! { p() {"At least 8 characters"} });
+            let view = SsrDom::try_from(rsx! { form() { {input} {help} } }).unwrap();
+            let json = view.to_json().await;
+            let described_by = &json["children"][0]["attrs"]["aria-describedby"];
+            assert!(described_by.is_string());
+            assert_eq!(described_by, &json["children"][1]["attrs"]["id"]);
+
+            let (first, _) = ViewBuilder::element("input").with_aria_describedby("a".into());
+            let (second, _) = ViewBuilder::element("input").with_aria_describedby("b".into());
+            let first = SsrDom::try_from(first).unwrap().html_string().await;
+            let second = SsrDom::try_from(second).unwrap().html_string().await;
+            assert_ne!(first, second);
+
+            let (input, _) = ViewBuilder::element("input")
+                .with_aria_describedby(rsx! { p(id = "help") {"Help"} });
+            assert_eq!(
+                SsrDom::try_from(input).unwrap().html_string().await,
+                r#"<input aria-describedby="help" />"#
+            );
+        });
+    }
+
+    #[test]
+    fn ssr_test_id() {
+        futures_lite::future::block_on(async {
+            let view = SsrDom::try_from(rsx! {
+                button(test:id = "submit") {"Submit"}
+            })
+            .unwrap();
+            #[cfg(feature = "testids")]
+            assert_eq!(
+                view.html_string().await,
+                r#"<button data-testid="submit">Submit</button>"#
+            );
+            #[cfg(not(feature = "testids"))]
+            assert_eq!(view.html_string().await, "<button>Submit</button>");
+        });
+    }
+
     #[test]
     fn can_use_string_stream_as_child() {
         futures::executor::block_on(async {
@@ -548,9 +977,363 @@ mod nonwasm {
                     .await
                     .unwrap();
                 })
-                .await;
-            // ANCHOR_END:patch_children_rsx
-        });
+                .await;
+            // ANCHOR_END:patch_children_rsx
+        });
+    }
+
+    #[test]
+    fn patch_children_reversed() {
+        futures::executor::block_on(async {
+            let (tx, rx) = mpsc::bounded(1);
+            let view = SsrDom::try_from(rsx! {
+                ol(patch:children:reversed = rx) {}
+            })
+            .unwrap();
+
+            view.executor
+                .run(async {
+                    for n in 0..3 {
+                        tx.send(ListPatch::push(rsx! { li() {{format!("{}", n)}} }))
+                            .await
+                            .unwrap();
+                    }
+                    tx.send(ListPatch::remove(0)).await.unwrap();
+                    repeat_times(0.1, 10, || async {
+                        view.html_string().await == r#"<ol><li>2</li> <li>1</li></ol>"#
+                    })
+                    .await
+                    .unwrap();
+                })
+                .await;
+        });
+    }
+
+    #[test]
+    fn append_reversed() {
+        futures::executor::block_on(async {
+            let view = SsrDom::try_from(
+                ViewBuilder::element("ol")
+                    .append_reversed((0..3).map(|n| rsx! { li() {{format!("{}", n)}} })),
+            )
+            .unwrap();
+            assert_eq!(
+                view.html_string().await,
+                r#"<ol><li>2</li> <li>1</li> <li>0</li></ol>"#
+            );
+        });
+    }
+
+    #[test]
+    fn sorted_children() {
+        futures::executor::block_on(async {
+            let model = mogwai::model::ListPatchModel::<u32>::new();
+            model.patch(ListPatch::push(3)).await.unwrap();
+            model.patch(ListPatch::push(1)).await.unwrap();
+            let built = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+            let view = SsrDom::try_from(ViewBuilder::element("ol").sorted_children(
+                &model,
+                |n| *n,
+                {
+                    let built = built.clone();
+                    move |n| {
+                        built.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                        rsx! { li() {{format!("{}", n)}} }
+                    }
+                },
+            ))
+            .unwrap();
+
+            view.executor
+                .run(async {
+                    repeat_times(0.1, 10, || async {
+                        view.html_string().await == r#"<ol><li>1</li> <li>3</li></ol>"#
+                    })
+                    .await
+                    .unwrap();
+                    model.patch(ListPatch::push(2)).await.unwrap();
+                    repeat_times(0.1, 10, || async {
+                        view.html_string().await == r#"<ol><li>1</li> <li>2</li> <li>3</li></ol>"#
+                    })
+                    .await
+                    .unwrap();
+                    model.patch(ListPatch::replace(1, 4)).await.unwrap();
+                    repeat_times(0.1, 10, || async {
+                        view.html_string().await == r#"<ol><li>2</li> <li>3</li> <li>4</li></ol>"#
+                    })
+                    .await
+                    .unwrap();
+                })
+                .await;
+            assert_eq!(built.load(std::sync::atomic::Ordering::SeqCst), 4);
+        });
+    }
+
+    fn suspense_list(reveal_order: RevealOrder) -> (SsrDom, Vec<mpsc::Sender<()>>) {
+        let (txs, items): (Vec<_>, Vec<_>) = ["a", "b", "c"]
+            .into_iter()
+            .map(|name| {
+                let (tx, rx) = mpsc::bounded::<()>(1);
+                let section = async move {
+                    let _ = rx.recv().await;
+                    rsx! { p() {{name}} }
+                };
+                (tx, (ViewBuilder::text("..."), section))
+            })
+            .unzip();
+        let view = SsrDom::try_from(
+            ViewBuilder::element("div").with_suspense_list(items, reveal_order),
+        )
+        .unwrap();
+        (view, txs)
+    }
+
+    #[test]
+    fn suspense_list_forwards() {
+        futures::executor::block_on(async {
+            let (view, txs) = suspense_list(RevealOrder::Forwards);
+            view.executor
+                .run(async {
+                    assert_eq!(view.html_string().await, "<div>... ... ...</div>");
+                    txs[2].send(()).await.unwrap();
+                    mogwai::time::wait_millis(20).await;
+                    assert_eq!(view.html_string().await, "<div>... ... ...</div>");
+                    txs[0].send(()).await.unwrap();
+                    repeat_times(0.1, 10, || async {
+                        view.html_string().await == "<div><p>a</p> ... ...</div>"
+                    })
+                    .await
+                    .unwrap();
+                    txs[1].send(()).await.unwrap();
+                    repeat_times(0.1, 10, || async {
+                        view.html_string().await == "<div><p>a</p> <p>b</p> <p>c</p></div>"
+                    })
+                    .await
+                    .unwrap();
+                })
+                .await;
+        });
+    }
+
+    #[test]
+    fn suspense_list_together() {
+        futures::executor::block_on(async {
+            let (view, txs) = suspense_list(RevealOrder::Together);
+            view.executor
+                .run(async {
+                    for i in [2, 0] {
+                        txs[i].send(()).await.unwrap();
+                        mogwai::time::wait_millis(20).await;
+                        assert_eq!(view.html_string().await, "<div>... ... ...</div>");
+                    }
+                    txs[1].send(()).await.unwrap();
+                    repeat_times(0.1, 10, || async {
+                        view.html_string().await == "<div><p>a</p> <p>b</p> <p>c</p></div>"
+                    })
+                    .await
+                    .unwrap();
+                })
+                .await;
+        });
+    }
+
+    #[test]
+    fn set_if_changed_skips_equal_values() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        #[derive(Clone, Debug, PartialEq)]
+        enum Status {
+            Idle,
+            Busy,
+        }
+
+        let status = mogwai::model::Model::new(Status::Idle);
+        let renders = Arc::new(AtomicUsize::new(0));
+        let class = status.stream().map({
+            let renders = renders.clone();
+            move |status: Status| {
+                renders.fetch_add(1, Ordering::SeqCst);
+                format!("{:?}", status).to_lowercase()
+            }
+        });
+        let view = SsrDom::try_from(rsx! { div(class = class) {} }).unwrap();
+
+        futures::executor::block_on(view.executor.run(async {
+            let shows = |html: &'static str| {
+                let view = &view;
+                repeat_times(0.1, 10, move || async move { view.html_string().await == html })
+            };
+            shows(r#"<div class="idle"></div>"#).await.unwrap();
+            assert_eq!(renders.load(Ordering::SeqCst), 1);
+
+            assert!(status.set_if_changed(Status::Busy).await);
+            shows(r#"<div class="busy"></div>"#).await.unwrap();
+            assert!(!status.set_if_changed(Status::Busy).await);
+            mogwai::time::wait_millis(10).await;
+            assert_eq!(renders.load(Ordering::SeqCst), 2);
+        }));
+    }
+
+    #[test]
+    fn state_machine_view() {
+        use mogwai::state_machine::StateMachine;
+
+        #[derive(Clone, Debug, PartialEq)]
+        enum Load {
+            Loading,
+            Loaded(String),
+            Failed(String),
+        }
+
+        enum Response {
+            Ok(String),
+            Err(String),
+            Retry,
+        }
+
+        let machine = StateMachine::new(Load::Loading, |state: &Load, event| match (state, event) {
+            (Load::Loading, Response::Ok(body)) => Some(Load::Loaded(body)),
+            (Load::Loading, Response::Err(msg)) => Some(Load::Failed(msg)),
+            (Load::Failed(_), Response::Retry) => Some(Load::Loading),
+            _ => None,
+        });
+        let view = SsrDom::try_from(ViewBuilder::element("section").with_state_machine(
+            &machine,
+            |state| match state {
+                Load::Loading => rsx! { p() {"Loading..."} },
+                Load::Loaded(body) => rsx! { p() {{body}} },
+                Load::Failed(msg) => rsx! { p(class = "error") {{msg}} },
+            },
+        ))
+        .unwrap();
+
+        futures::executor::block_on(view.executor.run(async {
+            let shows = |html: &'static str| {
+                let view = &view;
+                repeat_times(0.1, 10, move || async move { view.html_string().await == html })
+            };
+            shows("<section><p>Loading...</p></section>").await.unwrap();
+            assert!(machine.transition(Response::Err("timed out".into())).await);
+            shows(r#"<section><p class="error">timed out</p></section>"#)
+                .await
+                .unwrap();
+            assert!(!machine.transition(Response::Ok("ignored".into())).await);
+            assert!(machine.transition(Response::Retry).await);
+            shows("<section><p>Loading...</p></section>").await.unwrap();
+            assert!(machine.transition(Response::Ok("hello".into())).await);
+            shows("<section><p>hello</p></section>").await.unwrap();
+        }));
+    }
+
+    #[test]
+    fn keyed_text_children() {
+        futures::executor::block_on(async {
+            let (tx, rx) = mpsc::bounded(1);
+            let view = SsrDom::try_from(ViewBuilder::element("pre").with_keyed_text_children(rx))
+                .unwrap();
+            let lines = |lines: &[(u32, &str)]| {
+                lines
+                    .iter()
+                    .map(|(k, s)| (*k, s.to_string()))
+                    .collect::<Vec<_>>()
+            };
+
+            view.executor
+                .run(async {
+                    for (next, expected) in [
+                        (lines(&[(0, "a"), (1, "b"), (2, "c")]), "<pre>a b c</pre>"),
+                        (lines(&[(0, "a"), (1, "b"), (2, "d")]), "<pre>a b d</pre>"),
+                        (lines(&[(2, "d"), (0, "a"), (3, "e")]), "<pre>d a e</pre>"),
+                        (lines(&[]), "<pre></pre>"),
+                    ] {
+                        tx.send(next).await.unwrap();
+                        repeat_times(0.1, 10, || async { view.html_string().await == expected })
+                            .await
+                            .unwrap();
+                    }
+                })
+                .await;
+        });
+    }
+
+    #[test]
+    fn fallible_child() {
+        let view = SsrDom::try_from(
+            rsx! { div() {} }
+                .with_fallible_child(|| Ok(rsx! { p() { "fine" } }))
+                .with_fallible_child(|| anyhow::bail!("no good")),
+        )
+        .unwrap();
+        let html = futures::executor::block_on(view.html_string());
+        if cfg!(debug_assertions) {
+            assert_eq!(
+                html,
+                r#"<div><p>fine</p> <pre style="color: red; border: 1px solid red;">no good</pre></div>"#
+            );
+        } else {
+            assert_eq!(html, "<div><p>fine</p></div>");
+        }
+    }
+
+    #[test]
+    fn hydration_reports_attribute_mismatch() {
+        use crate::view::js::{check_hydrated_attributes, HydrationError};
+
+        let server = SsrDom::try_from(rsx! { div(id = "main", class = "dark") {} }).unwrap();
+        let attribs = |builder: ViewBuilder| {
+            builder
+                .initial_values
+                .into_iter()
+                .filter_map(|update| match update {
+                    mogwai::view::Update::Attribute(patch) => Some(patch),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+        let found = |name: &str| server.get_attrib(name).unwrap();
+
+        let matching = attribs(rsx! { div(id = "main", class = "dark") {} });
+        assert!(check_hydrated_attributes("div", matching.into_iter(), found).is_ok());
+
+        // streamed values are not known up front, so they are not compared
+        let (_tx, rx) = mpsc::bounded::<String>(1);
+        let streaming = attribs(rsx! { div(id = "main", class = rx) {} });
+        assert!(check_hydrated_attributes("div", streaming.into_iter(), found).is_ok());
+
+        let mismatched = attribs(rsx! { div(id = "main", class = "light", title = "hi") {} });
+        let err = anyhow::Error::from(
+            check_hydrated_attributes("div", mismatched.into_iter(), found).unwrap_err(),
+        );
+        assert_eq!(
+            err.downcast_ref::<HydrationError>(),
+            Some(&HydrationError::AttributeMismatch {
+                tag: "div".into(),
+                name: "class".into(),
+                expected: "light".into(),
+                found: Some("dark".into()),
+            })
+        );
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    fn debug_on_drop_logs() {
+        let dropped = || crate::view::js::DROPPED_LABELS.with(|d| d.borrow().clone());
+
+        let mut dom = JsDom::from(wasm_bindgen::JsValue::NULL);
+        JsDom::debug_on_drop("my view")(&mut dom).unwrap();
+        // stands in for another clone of the view, as JsValues can only be
+        // cloned on wasm32
+        let clone_internals = dom.drop_log.clone();
+        drop(dom);
+        assert!(dropped().is_empty());
+        drop(clone_internals);
+        assert_eq!(dropped(), vec!["my view".to_string()]);
+
+        // other domains are left alone
+        let ssr = SsrDom::try_from(rsx! { div() {} }.debug_on_drop("my view")).unwrap();
+        let html = futures::executor::block_on(ssr.html_string());
+        assert_eq!(html, "<div></div>");
     }
 
     #[test]
@@ -793,116 +1576,562 @@ mod wasm {
         tx.broadcast("later".to_string()).await.unwrap();
         tx.until_empty().await;
 
-        assert_eq!(div_el.outer_html(), r#"<div class="later"></div>"#);
+        assert_eq!(div_el.outer_html(), r#"<div class="later"></div>"#);
+    }
+
+    #[wasm_bindgen_test]
+    async fn rx_style_jsx() {
+        let (tx, rx) = broadcast::bounded::<String>(1);
+        let div: JsDom = html! { <div style:display=("block", rx) /> }
+            .try_into()
+            .unwrap();
+        let div_el = div.clone_as::<web_sys::HtmlElement>().unwrap();
+        assert_eq!(
+            div_el.outer_html(),
+            r#"<div style="display: block;"></div>"#
+        );
+
+        tx.broadcast("none".to_string()).await.unwrap();
+        tx.until_empty().await;
+
+        assert_eq!(div_el.outer_html(), r#"<div style="display: none;"></div>"#);
+    }
+
+    #[wasm_bindgen_test]
+    async fn capture_view_and_contra_map() {
+        let (tx, mut rx) = broadcast::bounded::<()>(1);
+        let _div: JsDom = html! {
+            <div id="hello" capture:view=tx.contra_map(|_: JsDom| ())>
+                "Hello there"
+            </div>
+        }
+        .try_into()
+        .unwrap();
+
+        let () = rx.recv().await.unwrap();
+    }
+
+    #[wasm_bindgen_test]
+    pub async fn initial_text() {
+        let dom = JsDom::try_from(ViewBuilder::text((
+            "hello",
+            stream::once("goodbye".to_string()),
+        )))
+        .unwrap();
+        assert_eq!("hello", dom.html_string().await);
+    }
+
+    #[wasm_bindgen_test]
+    pub async fn initial_text_nested() {
+        let dom = JsDom::try_from(ViewBuilder::element("div").append(ViewBuilder::text((
+            "hello",
+            stream::once("goodbye".to_string()),
+        ))))
+        .unwrap();
+        assert_eq!("<div>hello</div>", dom.html_string().await);
+    }
+
+    #[wasm_bindgen_test]
+    pub async fn rx_text() {
+        let (tx, rx) = broadcast::bounded::<String>(1);
+
+        let div: JsDom = html! {
+            <div>{("initial", rx)}</div>
+        }
+        .try_into()
+        .unwrap();
+
+        let el = div.clone_as::<web_sys::HtmlElement>().unwrap();
+        assert_eq!(el.inner_text().as_str(), "initial");
+
+        tx.broadcast("after".into()).await.unwrap();
+        tx.until_empty().await;
+
+        assert_eq!(el.inner_text(), "after");
+    }
+
+    #[wasm_bindgen_test]
+    async fn tx_on_click() {
+        let (tx, rx) = broadcast::bounded(1);
+
+        log::info!("test!");
+        let rx = rx.scan(0, |n: &mut i32, _: JsDomEvent| {
+            log::info!("event!");
+            *n += 1;
+            Some(if *n == 1 {
+                "Clicked 1 time".to_string()
+            } else {
+                format!("Clicked {} times", *n)
+            })
+        });
+
+        let button: JsDom = html! {
+            <button on:click=tx.clone()>{("Clicked 0 times", rx)}</button>
+        }
+        .try_into()
+        .unwrap();
+
+        let el = button.clone_as::<web_sys::HtmlElement>().unwrap();
+        assert_eq!(el.inner_html(), "Clicked 0 times");
+
+        el.click();
+        tx.until_empty().await;
+        let _ = wait_millis(1000).await;
+
+        assert_eq!(el.inner_html(), "Clicked 1 time");
+    }
+
+    //fn nice_compiler_error() {
+    //    let _div = html! {
+    //        <div unknown:colon:thing="not ok" />
+    //    };
+    //}
+
+    #[wasm_bindgen_test]
+    async fn can_hydrate_keyed_siblings() {
+        let container = JsDom::try_from(rsx! { div(id = "hydrator_keyed") {} }).unwrap();
+        let container_el: HtmlElement = container.clone_as::<HtmlElement>().unwrap();
+        container.run().unwrap();
+
+        for (i, between) in ["", "\n    ", " stray text "].into_iter().enumerate() {
+            container_el.set_inner_html(&format!(
+                r#"<ul id="keyed{i}"><li data-mogwai-key="a">A</li>{between}<li data-mogwai-key="b">B</li></ul>"#
+            ));
+            let (tx_class, rx_class) = mpsc::bounded::<String>(1);
+            let builder = ViewBuilder::element("ul")
+                .with_single_attrib_stream("id", format!("keyed{i}"))
+                .append(rsx! { li() {"A"} }.with_key("a"))
+                .append(rsx! { li(class = rx_class) {"B"} }.with_key("b"));
+            let _hydrated = JsDom::from(Hydrator::try_from(builder).unwrap());
+
+            tx_class.send("hydrated".to_string()).await.unwrap();
+            let expected = format!(
+                r#"<ul id="keyed{i}"><li data-mogwai-key="a">A</li>{between}<li data-mogwai-key="b" class="hydrated">B</li></ul>"#
+            );
+            wait_while(1.0, || container_el.inner_html() != expected)
+                .await
+                .unwrap();
+        }
+    }
+
+    #[wasm_bindgen_test]
+    async fn can_get_text() {
+        let text = JsDom::try_from(ViewBuilder::text("clicked 0 times")).unwrap();
+        assert_eq!(text.get_text().unwrap(), "clicked 0 times");
+        text.update(mogwai::view::Update::Text("clicked 1 time".into()))
+            .unwrap();
+        assert_eq!(text.get_text().unwrap(), "clicked 1 time");
+
+        let el = JsDom::try_from(ViewBuilder::element("p")).unwrap();
+        assert!(el.get_text().is_err());
+    }
+
+    #[wasm_bindgen_test]
+    async fn can_capture_for_each_typed() {
+        let (tx, rx) = mpsc::bounded(1);
+        let (tx_done, mut rx_done) = mpsc::bounded(1);
+        let dom = JsDom::try_from(ViewBuilder::element("input").with_capture_for_each(
+            JsDom::capture_for_each(
+                rx.map(|n: usize| format!("{}", n)),
+                web_sys::HtmlInputElement::set_value,
+            ),
+        ))
+        .expect("could not build dom");
+
+        wasm_bindgen_futures::spawn_local(async move {
+            for n in 0..3 {
+                tx.send(n).await.unwrap();
+            }
+            tx_done.send(()).await.unwrap();
+        });
+
+        dom.run_while(async move {
+            let _ = rx_done.next().await;
+        })
+        .await
+        .unwrap();
+
+        let value = dom
+            .visit_as(|input: &web_sys::HtmlInputElement| input.value())
+            .unwrap();
+        assert_eq!("2", value.as_str());
+    }
+
+    #[wasm_bindgen_test]
+    async fn can_set_inner_html() {
+        let view = JsDom::try_from(rsx! { div() { p() {"replaced"} } }).unwrap();
+        view.set_inner_html("<b>hi</b>").unwrap();
+        let el: HtmlElement = view.clone_as::<HtmlElement>().unwrap();
+        assert_eq!(el.outer_html(), "<div><b>hi</b></div>");
+        assert!(view.children.try_read().unwrap().is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    async fn animation_frames_are_monotonic() {
+        let frames = JsDom::animation_frames().take(3).collect::<Vec<_>>().await;
+        assert_eq!(frames.len(), 3);
+        assert!(frames.windows(2).all(|ts| ts[0] < ts[1]), "{:?}", frames);
+    }
+
+    #[wasm_bindgen_test]
+    async fn can_query_selector() {
+        let view = JsDom::try_from(rsx! {
+            div() {
+                ul() {
+                    li(class = "item") {"one"}
+                    li(class = "item selected") {"two"}
+                }
+                p(class = "item") {"three"}
+            }
+        })
+        .unwrap();
+
+        let selected = view.query_selector(".selected").unwrap();
+        let selected: HtmlElement = selected.clone_as::<HtmlElement>().unwrap();
+        assert_eq!(selected.inner_text(), "two");
+
+        let items = view
+            .query_selector_all(".item")
+            .into_iter()
+            .map(|dom| dom.clone_as::<HtmlElement>().unwrap().inner_text())
+            .collect::<Vec<_>>();
+        assert_eq!(items, vec!["one", "two", "three"]);
+
+        assert!(view.query_selector(".missing").is_none());
+        assert!(view.query_selector_all("li:oops(").is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    async fn can_modify_attribs() {
+        use mogwai::{patch::HashPatch, view::Update};
+
+        let view = JsDom::try_from(rsx! { a(class = "nav-link") {} }).unwrap();
+        let add_active = |class: Option<&String>| {
+            Some(match class {
+                Some(class) => format!("{} active", class),
+                None => "active".to_string(),
+            })
+        };
+        view.update(Update::Attribute(HashPatch::modify(
+            "class".to_string(),
+            add_active,
+        )))
+        .unwrap();
+        view.update(Update::Attribute(HashPatch::modify(
+            "title".to_string(),
+            add_active,
+        )))
+        .unwrap();
+        let el: HtmlElement = view.clone_as::<HtmlElement>().unwrap();
+        assert_eq!(
+            el.outer_html(),
+            r#"<a class="nav-link active" title="active"></a>"#
+        );
+    }
+
+    #[wasm_bindgen_test]
+    async fn can_batch_child_patches() {
+        let (tx, rx) = mpsc::bounded::<ListPatch<ViewBuilder>>(1);
+        let view: JsDom = rsx! {
+            ol(patch:children = rx) {
+                li() {"one"}
+            }
+        }
+        .try_into()
+        .unwrap();
+        let dom: HtmlElement = view.clone_as::<HtmlElement>().unwrap();
+        view.run().unwrap();
+
+        // a batch of pushes is appended in one go
+        tx.send(ListPatch::batch(vec![
+            ListPatch::push(rsx! { li() {"two"} }),
+            ListPatch::push(rsx! { li() {"three"} }),
+        ]))
+        .await
+        .unwrap();
+        wait_while(1.0, || {
+            dom.inner_html() != "<li>one</li><li>two</li><li>three</li>"
+        })
+        .await
+        .unwrap();
+
+        tx.send(ListPatch::batch(vec![
+            ListPatch::insert(0, rsx! { li() {"zero"} }),
+            ListPatch::pop(),
+            ListPatch::move_item(2, 1),
+        ]))
+        .await
+        .unwrap();
+        wait_while(1.0, || dom.inner_html() != "<li>zero</li><li>two</li><li>one</li>")
+            .await
+            .unwrap();
+    }
+
+    #[wasm_bindgen_test]
+    async fn can_receive_typed_input_value() {
+        let (tx, mut rx) = mpsc::bounded::<String>(1);
+        let view = JsDom::try_from(rsx! {
+            input(on:input(value) = tx) {}
+        })
+        .unwrap();
+        let input = view.clone_as::<web_sys::HtmlInputElement>().unwrap();
+        input.set_value("hello");
+        input
+            .dispatch_event(&web_sys::Event::new("input").unwrap())
+            .unwrap();
+        assert_eq!(rx.next().await.as_deref(), Some("hello"));
+    }
+
+    #[wasm_bindgen_test]
+    async fn can_bind_an_input_value() {
+        let name = mogwai::relay::FanInput::<String>::default();
+        let mut seen = name.stream();
+        let view = JsDom::try_from(rsx! {
+            input(bind:value = name) {}
+        })
+        .unwrap();
+        let input = view.clone_as::<web_sys::HtmlInputElement>().unwrap();
+        view.run().unwrap();
+
+        input.set_value("h");
+        input
+            .dispatch_event(&web_sys::Event::new("input").unwrap())
+            .unwrap();
+        assert_eq!(seen.next().await.as_deref(), Some("h"));
+
+        name.set("hi").await.unwrap();
+        assert_eq!(seen.next().await.as_deref(), Some("hi"));
+        wait_while(1.0, || input.value() != "hi").await.unwrap();
+    }
+
+    #[wasm_bindgen_test]
+    async fn can_insert_adjacent_to_a_reference() {
+        let list = JsDom::try_from(rsx! {
+            ul() {
+                li(id = "first") {"first"}
+                li(id = "last") {"last"}
+            }
+        })
+        .unwrap();
+        let reference = list.query_selector("#first").unwrap();
+        let middle = JsDom::try_from(rsx! { li(id = "middle") {"middle"} }).unwrap();
+        reference
+            .insert_adjacent(AdjacentPosition::AfterEnd, &middle)
+            .unwrap();
+        let ids = list
+            .children()
+            .iter()
+            .filter_map(|child| child.clone_as::<web_sys::Element>())
+            .map(|el| el.id())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, ["first", "middle", "last"]);
+    }
+
+    #[wasm_bindgen_test]
+    async fn insert_adjacent_takes_a_node_from_its_old_parent() {
+        let old_list = JsDom::try_from(rsx! { ul() {} }).unwrap();
+        let item = JsDom::try_from(rsx! { li(id = "moved") {"moved"} }).unwrap();
+        let _ = old_list.patch(ListPatch::push(item));
+        // the clone owned by the old parent, which knows its parent
+        let item = old_list.children.try_read().unwrap()[0].clone();
+
+        let new_list = JsDom::try_from(rsx! { ul() {} }).unwrap();
+        new_list
+            .insert_adjacent(AdjacentPosition::BeforeEnd, &item)
+            .unwrap();
+        assert!(old_list.children.try_read().unwrap().is_empty());
+        assert_eq!(new_list.children.try_read().unwrap().len(), 1);
+
+        // later patches on the old parent still target the right nodes
+        let stays = JsDom::try_from(rsx! { li(id = "stays") {"stays"} }).unwrap();
+        let _ = old_list.patch(ListPatch::push(stays));
+        let _ = old_list.patch(ListPatch::remove(0));
+        assert_eq!(old_list.html_string().await, "<ul></ul>");
+        assert_eq!(
+            new_list.html_string().await,
+            r#"<ul><li id="moved">moved</li></ul>"#
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn can_downcast_ref_a_dom_event() {
+        use mogwai::view::Downcast;
+
+        let ev = web_sys::Event::new("click").unwrap();
+        let event: DomEvent = AnyEvent::new(JsDomEvent::from(ev)).downcast().unwrap();
+        let browser_event = event.downcast_ref::<web_sys::Event>().unwrap();
+        assert_eq!(browser_event.type_(), "click");
+        assert!(event.downcast_ref::<web_sys::KeyboardEvent>().is_none());
+        // the event was only borrowed
+        let js = event.as_either_ref().left().unwrap().clone();
+        assert!(js.browser_event().is_some());
+    }
+
+    #[wasm_bindgen_test]
+    fn can_focus_and_blur_an_input() {
+        let view = JsDom::try_from(rsx! { input(id = "focus_me") {} }).unwrap();
+        let dom: HtmlElement = view.clone_as::<HtmlElement>().unwrap();
+        view.clone().run().unwrap();
+        let active_id = || {
+            crate::utils::DOCUMENT
+                .with(|d| d.active_element())
+                .map(|el| el.id())
+        };
+
+        view.focus().unwrap();
+        assert_eq!(active_id().as_deref(), Some("focus_me"));
+        view.scroll_into_view(false).unwrap();
+        view.blur().unwrap();
+        assert_ne!(active_id().as_deref(), Some("focus_me"));
+
+        dom.remove();
     }
 
     #[wasm_bindgen_test]
-    async fn rx_style_jsx() {
-        let (tx, rx) = broadcast::bounded::<String>(1);
-        let div: JsDom = html! { <div style:display=("block", rx) /> }
-            .try_into()
-            .unwrap();
-        let div_el = div.clone_as::<web_sys::HtmlElement>().unwrap();
-        assert_eq!(
-            div_el.outer_html(),
-            r#"<div style="display: block;"></div>"#
-        );
+    fn can_wrap_existing_nodes() {
+        let tag_name = |dom: &JsDom| {
+            dom.visit_as(|el: &web_sys::Element| el.tag_name())
+                .unwrap()
+                .to_lowercase()
+        };
+        let create = |tag: &str| {
+            crate::utils::DOCUMENT
+                .with(|d| d.create_element(tag))
+                .unwrap()
+        };
 
-        tx.broadcast("none".to_string()).await.unwrap();
-        tx.until_empty().await;
+        let from_element = JsDom::from(create("section"));
+        assert_eq!(tag_name(&from_element), "section");
 
-        assert_eq!(div_el.outer_html(), r#"<div style="display: none;"></div>"#);
+        let from_html_element = JsDom::from(create("button").unchecked_into::<HtmlElement>());
+        assert_eq!(tag_name(&from_html_element), "button");
+
+        let from_node = JsDom::from(create("ul").unchecked_into::<web_sys::Node>());
+        assert_eq!(tag_name(&from_node), "ul");
     }
 
     #[wasm_bindgen_test]
-    async fn capture_view_and_contra_map() {
-        let (tx, mut rx) = broadcast::bounded::<()>(1);
-        let _div: JsDom = html! {
-            <div id="hello" capture:view=tx.contra_map(|_: JsDom| ())>
-                "Hello there"
-            </div>
+    fn can_read_outer_html_sync() {
+        let view: JsDom = rsx! {
+            div(id = "sync", class = "snapshot") {
+                p() { "hello" }
+            }
         }
         .try_into()
         .unwrap();
+        assert_eq!(
+            view.outer_html_sync().as_deref(),
+            Some(r#"<div id="sync" class="snapshot"><p>hello</p></div>"#)
+        );
 
-        let () = rx.recv().await.unwrap();
-    }
-
-    #[wasm_bindgen_test]
-    pub async fn initial_text() {
-        let dom = JsDom::try_from(ViewBuilder::text((
-            "hello",
-            stream::once("goodbye".to_string()),
-        )))
-        .unwrap();
-        assert_eq!("hello", dom.html_string().await);
+        let text = JsDom::text("just text").unwrap();
+        assert_eq!(text.outer_html_sync().as_deref(), Some("just text"));
     }
 
     #[wasm_bindgen_test]
-    pub async fn initial_text_nested() {
-        let dom = JsDom::try_from(ViewBuilder::element("div").append(ViewBuilder::text((
-            "hello",
-            stream::once("goodbye".to_string()),
-        ))))
-        .unwrap();
-        assert_eq!("<div>hello</div>", dom.html_string().await);
+    fn fragment_cannot_be_patched_in() {
+        let view: JsDom = rsx! { ul() { li() {"one"} } }.try_into().unwrap();
+        let fragment = ViewBuilder::fragment(vec![rsx! { li() {"two"} }, rsx! { li() {"three"} }]);
+        assert!(view
+            .update(Update::Child(ListPatch::push(fragment)))
+            .is_err());
+
+        // later patches still target the right children
+        view.update(Update::Child(ListPatch::push(rsx! { li() {"two"} })))
+            .unwrap();
+        view.update(Update::Child(ListPatch::remove(0))).unwrap();
+        assert_eq!(
+            view.outer_html_sync().as_deref(),
+            Some("<ul><li>two</li></ul>")
+        );
     }
 
     #[wasm_bindgen_test]
-    pub async fn rx_text() {
-        let (tx, rx) = broadcast::bounded::<String>(1);
-
-        let div: JsDom = html! {
-            <div>{("initial", rx)}</div>
+    async fn can_replace_a_child() {
+        let (tx, rx) = mpsc::bounded::<ListPatch<ViewBuilder>>(1);
+        let view: JsDom = rsx! {
+            ol(patch:children = rx) {
+                li() {"one"}
+                li() {"two"}
+                li() {"three"}
+            }
         }
         .try_into()
         .unwrap();
+        let dom: HtmlElement = view.clone_as::<HtmlElement>().unwrap();
+        view.run().unwrap();
+        let children = || {
+            let list = dom.child_nodes();
+            (0..list.length())
+                .filter_map(|i| list.get(i))
+                .collect::<Vec<_>>()
+        };
+        let before = children();
 
-        let el = div.clone_as::<web_sys::HtmlElement>().unwrap();
-        assert_eq!(el.inner_text().as_str(), "initial");
-
-        tx.broadcast("after".into()).await.unwrap();
-        tx.until_empty().await;
+        tx.send(ListPatch::replace(1, rsx! { li() {"deux"} }))
+            .await
+            .unwrap();
+        wait_while(1.0, || {
+            dom.inner_html() != "<li>one</li><li>deux</li><li>three</li>"
+        })
+        .await
+        .unwrap();
 
-        assert_eq!(el.inner_text(), "after");
+        // only the target node changed
+        let after = children();
+        assert!(after[0].is_same_node(Some(&before[0])));
+        assert!(!after[1].is_same_node(Some(&before[1])));
+        assert!(after[2].is_same_node(Some(&before[2])));
     }
 
     #[wasm_bindgen_test]
-    async fn tx_on_click() {
-        let (tx, rx) = broadcast::bounded(1);
-
-        log::info!("test!");
-        let rx = rx.scan(0, |n: &mut i32, _: JsDomEvent| {
-            log::info!("event!");
-            *n += 1;
-            Some(if *n == 1 {
-                "Clicked 1 time".to_string()
-            } else {
-                format!("Clicked {} times", *n)
-            })
-        });
-
-        let button: JsDom = html! {
-            <button on:click=tx.clone()>{("Clicked 0 times", rx)}</button>
+    async fn moving_a_child_keeps_focus() {
+        let (tx, rx) = mpsc::bounded::<ListPatch<ViewBuilder>>(1);
+        let view: JsDom = rsx! {
+            ol(patch:children = rx) {
+                li() { input(id = "zero") {} }
+                li() { input(id = "one") {} }
+                li() { input(id = "two") {} }
+            }
         }
         .try_into()
         .unwrap();
+        let dom: HtmlElement = view.clone_as::<HtmlElement>().unwrap();
+        view.run().unwrap();
 
-        let el = button.clone_as::<web_sys::HtmlElement>().unwrap();
-        assert_eq!(el.inner_html(), "Clicked 0 times");
+        let input_ids = || {
+            let inputs = dom.query_selector_all("input").unwrap();
+            (0..inputs.length())
+                .map(|i| inputs.get(i).unwrap().unchecked_into::<web_sys::Element>().id())
+                .collect::<Vec<_>>()
+        };
+        let active_id = || {
+            crate::utils::DOCUMENT
+                .with(|d| d.active_element())
+                .map(|el| el.id())
+        };
 
-        el.click();
-        tx.until_empty().await;
-        let _ = wait_millis(1000).await;
+        let two = crate::utils::DOCUMENT
+            .with(|d| d.get_element_by_id("two"))
+            .unwrap()
+            .unchecked_into::<HtmlElement>();
+        two.focus().unwrap();
+        assert_eq!(active_id().as_deref(), Some("two"));
 
-        assert_eq!(el.inner_html(), "Clicked 1 time");
-    }
+        tx.send(ListPatch::move_item(2, 0)).await.unwrap();
+        wait_while(1.0, || input_ids() != ["two", "zero", "one"])
+            .await
+            .unwrap();
+        assert_eq!(active_id().as_deref(), Some("two"));
 
-    //fn nice_compiler_error() {
-    //    let _div = html! {
-    //        <div unknown:colon:thing="not ok" />
-    //    };
-    //}
+        tx.send(ListPatch::move_item(0, 2)).await.unwrap();
+        wait_while(1.0, || input_ids() != ["zero", "one", "two"])
+            .await
+            .unwrap();
+        assert_eq!(active_id().as_deref(), Some("two"));
+
+        dom.remove();
+    }
 
     #[wasm_bindgen_test]
     async fn can_patch_children() {
@@ -1048,6 +2277,72 @@ mod wasm {
             .unwrap();
     }
 
+    #[wasm_bindgen_test]
+    async fn keyed_text_children_reuse_text_nodes() {
+        let (tx, rx) = mpsc::bounded::<Vec<(usize, String)>>(1);
+        let view = JsDom::try_from(ViewBuilder::element("pre").with_keyed_text_children(rx))
+            .unwrap();
+        let dom: HtmlElement = view.clone_as().unwrap();
+        view.run().unwrap();
+
+        let lines = |last: &str| {
+            vec![
+                (0, "first\n".to_string()),
+                (1, "second\n".to_string()),
+                (2, last.to_string()),
+            ]
+        };
+        tx.send(lines("third")).await.unwrap();
+        wait_while(1.0, || dom.inner_html() != "first\nsecond\nthird")
+            .await
+            .unwrap();
+        let nodes = dom.child_nodes();
+        let before = (0..nodes.length())
+            .map(|i| nodes.get(i).unwrap())
+            .collect::<Vec<_>>();
+
+        tx.send(lines("third, updated")).await.unwrap();
+        wait_while(1.0, || dom.inner_html() != "first\nsecond\nthird, updated")
+            .await
+            .unwrap();
+        let nodes = dom.child_nodes();
+        assert_eq!(nodes.length(), 3);
+        for (i, node) in before.iter().enumerate() {
+            assert!(node.is_same_node(nodes.get(i as u32).as_ref()));
+        }
+        assert_eq!(
+            before[0].dyn_ref::<web_sys::Text>().unwrap().data(),
+            "first\n"
+        );
+        assert_eq!(
+            before[2].dyn_ref::<web_sys::Text>().unwrap().data(),
+            "third, updated"
+        );
+    }
+
+    #[wasm_bindgen_test]
+    async fn can_replace_children() {
+        let dom = Dom::try_from(rsx! {
+            ul() {
+                li() { "a" }
+                li() { "b" }
+            }
+        })
+        .unwrap();
+        dom.replace_children(vec![
+            rsx! { li() { "c" } },
+            rsx! { li() { "d" } },
+            rsx! { li() { "e" } },
+        ])
+        .unwrap();
+        assert_eq!(
+            dom.html_string().await,
+            "<ul><li>c</li><li>d</li><li>e</li></ul>"
+        );
+        let js = dom.as_either_ref().left().unwrap();
+        assert_eq!(js.children.try_read().unwrap().len(), 3);
+    }
+
     #[wasm_bindgen_test]
     pub async fn can_use_string_stream_as_child() {
         let clicks = futures::stream::iter(vec![0, 1, 2]);
@@ -1139,6 +2434,31 @@ mod wasm {
         log::info!("updated text");
     }
 
+    #[wasm_bindgen_test]
+    async fn hydrating_mismatched_attribute_errors() {
+        let container = JsDom::try_from(rsx! { div(id = "hydrator_mismatch") {} }).unwrap();
+        let container_el: HtmlElement = container.clone_as::<HtmlElement>().unwrap();
+        container.run().unwrap();
+        container_el.set_inner_html(r#"<div id="mismatch"><p class="dark">text</p></div>"#);
+
+        let err = Hydrator::try_from(rsx! {
+            div(id = "mismatch") {
+                p(class = "light") {"text"}
+            }
+        })
+        .err()
+        .unwrap();
+        assert_eq!(
+            err.downcast_ref::<mogwai_dom::view::js::HydrationError>(),
+            Some(&mogwai_dom::view::js::HydrationError::AttributeMismatch {
+                tag: "p".into(),
+                name: "class".into(),
+                expected: "light".into(),
+                found: Some("dark".into()),
+            })
+        );
+    }
+
     #[wasm_bindgen_test]
     async fn can_capture_for_each() {
         let (tx, rx) = mpsc::bounded(1);
@@ -1281,6 +2601,291 @@ mod wasm {
         });
         time::wait_millis(10).await;
     }
+
+    #[wasm_bindgen_test]
+    async fn task_on_first_runs_once() {
+        let (tx, mut rx) = mpsc::bounded::<()>(3);
+        let dom = JsDom::try_from(ViewBuilder::element("button").with_task_on_first(
+            "click",
+            move |_: JsDom| async move {
+                tx.send(()).await.unwrap();
+            },
+        ))
+        .unwrap();
+        let button = dom.clone_as::<HtmlElement>().unwrap();
+        for _ in 0..3 {
+            button.click();
+            wait_millis(2).await;
+        }
+        assert!(rx.next().await.is_some());
+        assert!(rx.next().await.is_none(), "task should only run once");
+    }
+
+    #[wasm_bindgen_test]
+    async fn can_dispatch_and_receive_custom_events() {
+        let dom = JsDom::try_from(rsx! { div() {} }).unwrap();
+        let mut details = dom.on_custom_event("my-event");
+        dom.dispatch_custom_event("my-event", wasm_bindgen::JsValue::from_str("hello"))
+            .unwrap();
+        let detail = details.next().await.unwrap();
+        assert_eq!(detail.as_string(), Some("hello".to_string()));
+    }
+
+    #[wasm_bindgen_test]
+    async fn can_inherit_attribs() {
+        let source = JsDom::try_from(rsx! {
+            div(id = "original", class = "card", title = "ignored") {}
+        })
+        .unwrap();
+        let copy = JsDom::try_from(rsx! {
+            section(post:build = JsDom::inherit_attribs_from(&source, &["class", "id", "lang"])) {}
+        })
+        .unwrap();
+        assert_eq!(
+            copy.html_string().await,
+            r#"<section class="card" id="original"></section>"#
+        );
+    }
+
+    #[wasm_bindgen_test]
+    async fn can_stream_content_changes() {
+        let dom = JsDom::try_from(rsx! { div(contenteditable = "true") {"Hello"} }).unwrap();
+        let mut changes = dom.on_content_change();
+        let div = dom.clone_as::<HtmlElement>().unwrap();
+        div.set_text_content(Some("Hello, world"));
+        div.dispatch_event(&web_sys::Event::new("input").unwrap())
+            .unwrap();
+        assert_eq!(changes.next().await, Some("Hello, world".to_string()));
+    }
+
+    #[wasm_bindgen_test]
+    async fn can_read_selected_files() {
+        let parts = js_sys::Array::of1(&wasm_bindgen::JsValue::from_str("hello"));
+        let file = web_sys::File::new_with_str_sequence(&parts, "hello.txt").unwrap();
+        let file = crate::web::file::SelectedFile::from(file);
+        assert_eq!(file.name(), "hello.txt");
+        assert_eq!(file.size(), 5);
+        assert_eq!(file.read_text().await.unwrap(), "hello");
+        assert_eq!(file.read_bytes().await.unwrap(), b"hello".to_vec());
+
+        let input = JsDom::try_from(rsx! { input(type = "file") {} }).unwrap();
+        let mut selections = input.on_file_select();
+        input
+            .clone_as::<HtmlElement>()
+            .unwrap()
+            .dispatch_event(&web_sys::Event::new("change").unwrap())
+            .unwrap();
+        assert!(selections.next().await.unwrap().is_empty());
+    }
+
+    #[cfg(feature = "idb")]
+    #[wasm_bindgen_test]
+    async fn can_round_trip_idb_values() {
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        struct Settings {
+            volume: u8,
+            name: String,
+        }
+
+        let store = crate::web::idb::open("mogwai-test", "settings").await.unwrap();
+        let settings = Settings {
+            volume: 11,
+            name: "loud".to_string(),
+        };
+        store.put("settings", &settings).await.unwrap();
+        assert_eq!(
+            store.get::<Settings>("settings").await.unwrap(),
+            Some(settings)
+        );
+        store.delete("settings").await.unwrap();
+        assert_eq!(store.get::<Settings>("settings").await.unwrap(), None);
+    }
+
+    #[wasm_bindgen_test]
+    async fn js_event_pointer_position() {
+        let init = web_sys::MouseEventInit::new();
+        init.set_client_x(12);
+        init.set_client_y(34);
+        let click =
+            web_sys::MouseEvent::new_with_mouse_event_init_dict("click", &init).unwrap();
+        let event: DomEvent = AnyEvent::new(JsDomEvent::from(web_sys::Event::from(click)))
+            .downcast()
+            .unwrap();
+        assert_eq!(event.pointer_position(), Some((12.0, 34.0)));
+
+        let keyup = web_sys::Event::new("keyup").unwrap();
+        let event: DomEvent = AnyEvent::new(JsDomEvent::from(keyup)).downcast().unwrap();
+        assert_eq!(event.pointer_position(), None);
+    }
+
+    #[wasm_bindgen_test]
+    async fn can_read_navigator_status() {
+        assert!(crate::web::navigator::is_online());
+        let _status = crate::web::navigator::online_status();
+        let _ = crate::web::navigator::can_share();
+    }
+
+    #[wasm_bindgen_test]
+    async fn can_listen_for_print_events() {
+        let mut before = crate::web::before_print();
+        let mut after = crate::web::after_print();
+        crate::utils::WINDOW.with(|w| {
+            for name in ["beforeprint", "afterprint"] {
+                w.dispatch_event(&web_sys::Event::new(name).unwrap()).unwrap();
+            }
+        });
+        assert_eq!(before.next().await, Some(()));
+        assert_eq!(after.next().await, Some(()));
+    }
+
+    #[wasm_bindgen_test]
+    async fn share_errors_without_user_gesture() {
+        // either the API is unsupported, or sharing outside of a user gesture
+        // is rejected
+        let result = crate::web::share(crate::web::ShareData {
+            title: Some("mogwai".to_string()),
+            url: Some("https://github.com/schell/mogwai".to_string()),
+            ..Default::default()
+        })
+        .await;
+        assert!(result.is_err());
+    }
+
+    #[wasm_bindgen_test]
+    async fn lazy_image_shows_placeholder_until_load() {
+        let placeholder = "data:image/gif;base64,R0lGODlhAQABAAAAACw=";
+        let img = JsDom::try_from(crate::web::lazy_image("/not-yet.jpg", placeholder)).unwrap();
+        let el = img.clone_as::<HtmlElement>().unwrap();
+        assert_eq!(el.get_attribute("loading").as_deref(), Some("lazy"));
+        let style = el.style();
+        assert!(style
+            .get_property_value("background-image")
+            .unwrap()
+            .contains(placeholder));
+        assert_eq!(style.get_property_value("filter").unwrap(), "blur(8px)");
+
+        el.dispatch_event(&web_sys::Event::new("load").unwrap())
+            .unwrap();
+        wait_millis(10).await;
+        assert_eq!(style.get_property_value("background-image").unwrap(), "none");
+        assert_eq!(style.get_property_value("filter").unwrap(), "none");
+    }
+
+    #[wasm_bindgen_test]
+    async fn lazy_component_builds_after_intersecting() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let builds = std::sync::Arc::new(AtomicUsize::new(0));
+        let lazy = crate::web::lazy_component(rsx! { p() {"Loading..."} }, {
+            let builds = builds.clone();
+            move || {
+                builds.fetch_add(1, Ordering::SeqCst);
+                rsx! { p() {"Built"} }
+            }
+        });
+        let page: JsDom = rsx! {
+            div() {
+                div(style:height = "10000px") {}
+                {lazy}
+            }
+        }
+        .try_into()
+        .unwrap();
+        let page_el = page.clone_as::<HtmlElement>().unwrap();
+        page.run().unwrap();
+
+        wait_millis(50).await;
+        assert_eq!(builds.load(Ordering::SeqCst), 0);
+        assert!(page_el.inner_html().contains("Loading..."));
+
+        let window = web_sys::window().unwrap();
+        window.scroll_to_with_x_and_y(0.0, 10000.0);
+        wait_while(1.0, || builds.load(Ordering::SeqCst) == 0)
+            .await
+            .unwrap();
+        wait_while(1.0, || !page_el.inner_html().contains("Built"))
+            .await
+            .unwrap();
+        assert_eq!(builds.load(Ordering::SeqCst), 1);
+
+        window.scroll_to_with_x_and_y(0.0, 0.0);
+        page_el.remove();
+    }
+
+    #[wasm_bindgen_test]
+    async fn roving_focus_moves_with_arrow_keys() {
+        let menu: JsDom = rsx! {
+            ul(post:build = crate::web::roving_focus(crate::web::Orientation::Vertical)) {
+                li() {"Open"}
+                li() {"Save"}
+                li() {"Quit"}
+            }
+        }
+        .try_into()
+        .unwrap();
+        let menu_el = menu.clone_as::<HtmlElement>().unwrap();
+        menu.run().unwrap();
+
+        let items = menu_el.children();
+        let item = |i: u32| items.item(i).unwrap();
+        let tabindexes = || {
+            (0..items.length())
+                .map(|i| item(i).get_attribute("tabindex").unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(tabindexes(), vec!["0", "-1", "-1"]);
+
+        let press = |key: &str| {
+            let init = web_sys::KeyboardEventInit::new();
+            init.set_key(key);
+            init.set_bubbles(true);
+            let ev = web_sys::KeyboardEvent::new_with_keyboard_event_init_dict("keydown", &init)
+                .unwrap();
+            let focused = crate::utils::DOCUMENT.with(|d| d.active_element()).unwrap();
+            focused.dispatch_event(&ev).unwrap();
+        };
+        let focused = || crate::utils::DOCUMENT.with(|d| d.active_element());
+
+        item(0).unchecked_into::<HtmlElement>().focus().unwrap();
+        press("ArrowDown");
+        assert_eq!(focused(), Some(item(1)));
+        assert_eq!(tabindexes(), vec!["-1", "0", "-1"]);
+        press("ArrowDown");
+        press("ArrowDown");
+        assert_eq!(focused(), Some(item(0)), "focus should wrap to the first item");
+        press("ArrowUp");
+        assert_eq!(focused(), Some(item(2)));
+        press("Home");
+        assert_eq!(focused(), Some(item(0)));
+        press("End");
+        assert_eq!(focused(), Some(item(2)));
+
+        menu_el.remove();
+    }
+
+    #[wasm_bindgen_test]
+    async fn can_save_and_restore_scroll_offset() {
+        let tall: JsDom = rsx! { div(style:height = "10000px") {} }.try_into().unwrap();
+        let tall_el: HtmlElement = tall.clone_as::<HtmlElement>().unwrap();
+        tall.run().unwrap();
+
+        let window = web_sys::window().unwrap();
+        window.scroll_to_with_x_and_y(0.0, 500.0);
+        crate::web::scroll_restoration::save("/long-page");
+        assert_eq!(
+            crate::web::scroll_restoration::saved("/long-page"),
+            Some((0.0, 500.0))
+        );
+
+        window.scroll_to_with_x_and_y(0.0, 0.0);
+        assert_eq!(window.scroll_y().unwrap(), 0.0);
+
+        assert!(crate::web::scroll_restoration::restore("/long-page"));
+        assert_eq!(window.scroll_y().unwrap(), 500.0);
+        assert!(!crate::web::scroll_restoration::restore("/elsewhere"));
+
+        tall_el.remove();
+    }
 }
 
 #[cfg(test)]
@@ -1362,6 +2967,49 @@ mod test {
         });
     }
 
+    #[test]
+    fn can_peek_at_captured() {
+        let capture: Captured<SsrDom> = Captured::default();
+        assert!(capture.current().is_none());
+        let dom = SsrDom::try_from(rsx! {
+            div(id="chappie", capture:view=capture.sink()){}
+        })
+        .unwrap();
+        futures::executor::block_on(dom.executor.run(async {
+            let captured = capture.current().unwrap();
+            assert_eq!(captured.html_string().await, r#"<div id="chappie"></div>"#);
+            // peeking doesn't consume the capture
+            let captured = capture.get().await;
+            assert_eq!(captured.html_string().await, r#"<div id="chappie"></div>"#);
+        }));
+    }
+
+    #[test]
+    fn can_capture_into_a_list() {
+        fn assert_send_sync<T: Send + Sync>(_: &T) {}
+        let list: CapturedList<SsrDom> = CapturedList::default();
+        assert_send_sync(&list);
+        let dom = SsrDom::try_from(rsx! {
+            ul() {
+                li(capture:view = list.sink()) {"one"}
+                li(capture:view = list.sink()) {"two"}
+                li(capture:view = list.sink()) {"three"}
+            }
+        })
+        .unwrap();
+        futures::executor::block_on(dom.executor.run(async {
+            let mut htmls = vec![];
+            for li in list.get_all().await {
+                htmls.push(li.html_string().await);
+            }
+            assert_eq!(htmls, ["<li>one</li>", "<li>two</li>", "<li>three</li>"]);
+
+            let streamed = list.stream().take(3).collect::<Vec<_>>().await;
+            assert_eq!(streamed.len(), 3);
+            assert_eq!(streamed[2].html_string().await, "<li>three</li>");
+        }));
+    }
+
     #[test]
     fn how_to_set_properties() {
         let mut stream_input_value = Input::<String>::default();
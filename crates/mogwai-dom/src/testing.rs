@@ -0,0 +1,40 @@
+//! Helpers for testing views.
+use mogwai::view::ViewBuilder;
+
+use crate::view::SsrDom;
+
+/// Build the view as an [`SsrDom`] and render it to an HTML string.
+pub fn ssr_html(view: impl Into<ViewBuilder>) -> anyhow::Result<String> {
+    let ssr = SsrDom::try_from(view.into())?;
+    Ok(futures_lite::future::block_on(ssr.html_string()))
+}
+
+/// Assert that a view renders to the expected HTML when it is server-side
+/// rendered.
+///
+/// The view may be anything that converts into a [`ViewBuilder`], eg. the
+/// output of a component function. Because [`ViewBuilder`] is domain
+/// agnostic the same component can then be built as a
+/// [`JsDom`](crate::view::JsDom) in the browser.
+///
+/// ```rust
+/// use mogwai_dom::prelude::*;
+///
+/// fn greeting(name: &str) -> ViewBuilder {
+///     rsx! { p(class = "greeting") { {format!("Hello, {}!", name)} } }
+/// }
+///
+/// mogwai_dom::assert_view_snapshot!(
+///     greeting("mogwai"),
+///     r#"<p class="greeting">Hello, mogwai!</p>"#
+/// );
+/// ```
+#[macro_export]
+macro_rules! assert_view_snapshot {
+    ($view:expr, $expected:expr $(,)?) => {
+        match $crate::testing::ssr_html($view) {
+            Ok(html) => assert_eq!(html, $expected, "view does not match its snapshot"),
+            Err(e) => panic!("could not build view: {:#}", e),
+        }
+    };
+}
@@ -19,11 +19,12 @@ use mogwai::{
     view::{AnyEvent, AnyView, Downcast, Listener, Update, ViewBuilder, ViewIdentity},
 };
 use send_wrapper::SendWrapper;
-use wasm_bindgen::{JsCast, JsValue, UnwrapThrowExt};
+use wasm_bindgen::{prelude::Closure, JsCast, JsValue, UnwrapThrowExt};
 
 use crate::{
     event::{JsDomEvent, WebCallback},
     prelude::{DOCUMENT, WINDOW},
+    web::file::{selected_files, SelectedFile},
 };
 
 use super::{atomic::AtomicOption, FutureTask};
@@ -80,6 +81,12 @@ impl<T> Clone for WeakShared<T> {
     }
 }
 
+impl<T> WeakShared<T> {
+    pub(crate) fn upgrade(&self) -> Option<Shared<T>> {
+        self.0.upgrade().map(Shared)
+    }
+}
+
 struct CancelStream<St> {
     st: St,
     waker: Shared<AtomicOption<Waker>>,
@@ -146,6 +153,86 @@ pub struct JsDom {
     pub(crate) children: Shared<RwLock<Vec<JsDom>>>,
     // a list of this element's parent's children, so that this element may remove itself
     pub(crate) parents_children: Option<WeakShared<RwLock<Vec<JsDom>>>>,
+    // logs when the last clone of this element is dropped, see `JsDom::debug_on_drop`
+    #[cfg(debug_assertions)]
+    pub(crate) drop_log: Shared<RwLock<Option<DropLog>>>,
+}
+
+/// Where to insert a node relative to a reference element, see
+/// [`JsDom::insert_adjacent`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AdjacentPosition {
+    /// Before the reference element itself.
+    BeforeBegin,
+    /// Inside the reference element, before its first child.
+    AfterBegin,
+    /// Inside the reference element, after its last child.
+    BeforeEnd,
+    /// After the reference element itself.
+    AfterEnd,
+}
+
+impl AdjacentPosition {
+    /// The position as expected by `Element.insertAdjacentElement`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AdjacentPosition::BeforeBegin => "beforebegin",
+            AdjacentPosition::AfterBegin => "afterbegin",
+            AdjacentPosition::BeforeEnd => "beforeend",
+            AdjacentPosition::AfterEnd => "afterend",
+        }
+    }
+}
+
+/// Logs its label when dropped.
+#[cfg(debug_assertions)]
+pub(crate) struct DropLog {
+    label: String,
+}
+
+#[cfg(debug_assertions)]
+impl DropLog {
+    fn new(label: String) -> Self {
+        DropLog { label }
+    }
+}
+
+#[cfg(all(debug_assertions, test))]
+thread_local! {
+    /// Labels of the dropped views on this thread, so tests can check them
+    /// without installing a global logger.
+    pub(crate) static DROPPED_LABELS: std::cell::RefCell<Vec<String>> =
+        const { std::cell::RefCell::new(Vec::new()) };
+}
+
+#[cfg(debug_assertions)]
+impl Drop for DropLog {
+    fn drop(&mut self) {
+        log::trace!("dropped JsDom '{}'", self.label);
+        #[cfg(test)]
+        DROPPED_LABELS.with(|labels| labels.borrow_mut().push(self.label.clone()));
+    }
+}
+
+/// Adds [`JsDom::debug_on_drop`] to [`ViewBuilder`].
+pub trait DebugOnDrop {
+    /// Log the given label with [`log::trace!`] when the last clone of the
+    /// built [`JsDom`] is dropped.
+    ///
+    /// Views built by other domains, eg. [`SsrDom`](super::SsrDom), are left
+    /// alone. It does nothing in release builds.
+    fn debug_on_drop(self, label: &str) -> Self;
+}
+
+impl DebugOnDrop for ViewBuilder {
+    fn debug_on_drop(mut self, label: &str) -> Self {
+        let f = JsDom::debug_on_drop(label);
+        self.post_build_ops
+            .push(Box::new(move |view: &mut AnyView| {
+                view.downcast_mut::<JsDom>().map_or(Ok(()), f)
+            }));
+        self
+    }
 }
 
 impl Downcast<JsDom> for AnyView {
@@ -189,10 +276,30 @@ impl From<JsValue> for JsDom {
             listener_callbacks: Default::default(),
             children: Default::default(),
             parents_children: None,
+            #[cfg(debug_assertions)]
+            drop_log: Default::default(),
         }
     }
 }
 
+impl From<web_sys::Node> for JsDom {
+    fn from(node: web_sys::Node) -> Self {
+        JsDom::from(JsValue::from(node))
+    }
+}
+
+impl From<web_sys::Element> for JsDom {
+    fn from(el: web_sys::Element) -> Self {
+        JsDom::from(JsValue::from(el))
+    }
+}
+
+impl From<web_sys::HtmlElement> for JsDom {
+    fn from(el: web_sys::HtmlElement) -> Self {
+        JsDom::from(JsValue::from(el))
+    }
+}
+
 // TODO: Make errors returned by JsDom methods Box<dyn Error>
 impl JsDom {
     /// Create a `JsDom` from anything that implements `JsCast`.
@@ -221,6 +328,183 @@ impl JsDom {
         })
     }
 
+    /// Pair a stream with a typed setter, for use with
+    /// [`ViewBuilder::with_capture_for_each`] or the `capture:for_each`
+    /// [`rsx`](crate::rsx) macro attribute.
+    ///
+    /// The setter is wrapped with [`JsDom::try_to`], so the view is cast to
+    /// `E` before each value is set:
+    /// ```rust, ignore
+    /// rsx! {
+    ///     input(
+    ///         capture:for_each = JsDom::capture_for_each(
+    ///             rx.map(|n: usize| format!("{}", n)),
+    ///             web_sys::HtmlInputElement::set_value,
+    ///         )
+    ///     ) {}
+    /// }
+    /// ```
+    pub fn capture_for_each<E, S, T, St>(
+        st: St,
+        set: impl Fn(&E, &S) + Send + 'static,
+    ) -> (St, impl Fn(&Self, T) + Send + 'static)
+    where
+        E: JsCast,
+        S: ?Sized,
+        T: AsRef<S> + 'static,
+        St: Stream<Item = T> + Send + Unpin + 'static,
+    {
+        (st, JsDom::try_to(set))
+    }
+
+    /// Return a post-build operation that copies the named attributes from
+    /// the `source` element onto the built element.
+    ///
+    /// The attributes are read from `source` when the view is built.
+    /// Attributes missing on `source` are skipped.
+    ///
+    /// This is useful in conjunction with the `post:build`
+    /// [`rsx`](crate::rsx) macro attribute, or
+    /// [`ViewBuilder::with_post_build`].
+    pub fn inherit_attribs_from(
+        source: &JsDom,
+        names: &[&str],
+    ) -> impl FnOnce(&mut JsDom) -> anyhow::Result<()> + Send + Sync + 'static {
+        let source = source.clone();
+        let names = names.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        move |dom: &mut JsDom| {
+            let from = source
+                .clone_as::<web_sys::Element>()
+                .context("source is not an element")?;
+            let to = dom
+                .clone_as::<web_sys::Element>()
+                .context("view is not an element")?;
+            for name in names.iter() {
+                if let Some(value) = from.get_attribute(name) {
+                    to.set_attribute(name, &value)
+                        .map_err(|_| anyhow::anyhow!("could not set attribute {}", name))?;
+                }
+            }
+            Ok(())
+        }
+    }
+
+    /// Log the given label with [`log::trace!`] when the last clone of the
+    /// view is dropped.
+    ///
+    /// This is a debugging aid for finding out when (or whether) a view is
+    /// dropped, eg. to find out why a child vanished. It does nothing in
+    /// release builds.
+    ///
+    /// This is meant to be used with the `post:build` [`rsx`](crate::rsx)
+    /// macro attribute, or [`ViewBuilder::with_post_build`]. See also
+    /// [`DebugOnDrop::debug_on_drop`].
+    pub fn debug_on_drop(
+        label: &str,
+    ) -> impl FnOnce(&mut JsDom) -> anyhow::Result<()> + Send + Sync + 'static {
+        #[cfg(debug_assertions)]
+        let label = label.to_string();
+        #[cfg(not(debug_assertions))]
+        let _ = label;
+        move |_dom: &mut JsDom| {
+            #[cfg(debug_assertions)]
+            {
+                *_dom.drop_log.try_write().context("cannot write drop log")? =
+                    Some(DropLog::new(label));
+            }
+            Ok(())
+        }
+    }
+
+    /// Get the text currently displayed by this text node.
+    ///
+    /// Fails if this is not a text node.
+    pub fn get_text(&self) -> anyhow::Result<String> {
+        self.visit_as(|text: &web_sys::Text| text.data())
+            .context("not a text node")
+    }
+
+    /// Returns this node's parent node, if it has one.
+    pub fn parent(&self) -> Option<JsDom> {
+        let parent = self.visit_as(|node: &web_sys::Node| node.parent_node())??;
+        Some(JsDom::from_jscast(&parent))
+    }
+
+    /// Returns this node's child nodes in order, including text nodes.
+    pub fn children(&self) -> Vec<JsDom> {
+        self.visit_as(|node: &web_sys::Node| {
+            let nodes = node.child_nodes();
+            (0..nodes.length())
+                .filter_map(|i| nodes.get(i))
+                .map(|child| JsDom::from_jscast(&child))
+                .collect()
+        })
+        .unwrap_or_default()
+    }
+
+    /// Returns the first descendant of this element that matches the CSS
+    /// selector `sel`, if any.
+    ///
+    /// Returns `None` if this is not an element, `sel` is not a valid
+    /// selector, or when not running on WASM.
+    pub fn query_selector(&self, sel: &str) -> Option<JsDom> {
+        if !cfg!(target_arch = "wasm32") {
+            return None;
+        }
+        let found = self
+            .visit_as(|el: &web_sys::Element| el.query_selector(sel))?
+            .ok()??;
+        Some(JsDom::from_jscast(&found))
+    }
+
+    /// Returns all descendants of this element that match the CSS selector
+    /// `sel`, in document order.
+    ///
+    /// Returns an empty `Vec` if this is not an element, `sel` is not a valid
+    /// selector, or when not running on WASM.
+    pub fn query_selector_all(&self, sel: &str) -> Vec<JsDom> {
+        if !cfg!(target_arch = "wasm32") {
+            return vec![];
+        }
+        self.visit_as(|el: &web_sys::Element| el.query_selector_all(sel))
+            .and_then(Result::ok)
+            .map(|list| {
+                (0..list.length())
+                    .filter_map(|i| list.get(i))
+                    .map(|node| JsDom::from_jscast(&node))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// A stream of the high-res timestamps of each animation frame, in
+    /// milliseconds.
+    ///
+    /// On WASM each timestamp is delivered by `requestAnimationFrame`. Frames
+    /// that arrive while the previous timestamp is still unread are skipped.
+    /// On other targets a timestamp from [`mogwai::time::now`] is yielded
+    /// roughly every 16 milliseconds.
+    ///
+    /// Dropping the stream stops requesting frames.
+    pub fn animation_frames() -> impl Stream<Item = f64> + Send + Unpin + 'static {
+        #[cfg(target_arch = "wasm32")]
+        {
+            let (tx, rx) = mpsc::bounded::<f64>(1);
+            crate::utils::request_animation_frame(move |ts| match tx.try_send(ts) {
+                Ok(()) | Err(async_channel::TrySendError::Full(_)) => true,
+                Err(async_channel::TrySendError::Closed(_)) => false,
+            });
+            rx
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            Box::pin(futures_lite::stream::unfold((), |()| async {
+                let _ = mogwai::time::wait_millis(16).await;
+                Some((mogwai::time::now(), ()))
+            }))
+        }
+    }
+
     pub fn update(&self, update: Update) -> anyhow::Result<()> {
         match update {
             Update::Text(s) => {
@@ -239,6 +523,11 @@ impl JsDom {
                         .remove_attribute(&k)
                         .map_err(|_| anyhow::anyhow!("could remove attrib"))?;
                 }
+                patch @ HashPatch::Modify { .. } => {
+                    let el = self.inner.unchecked_ref::<web_sys::Element>();
+                    let patch = patch.resolve(|k| el.get_attribute(k));
+                    self.update(Update::Attribute(patch))?;
+                }
             },
             Update::BooleanAttribute(patch) => match patch {
                 HashPatch::Insert(k, v) => {
@@ -262,6 +551,11 @@ impl JsDom {
                             anyhow::anyhow!("could not remove boolean attrib".to_string())
                         })?;
                 }
+                patch @ HashPatch::Modify { .. } => {
+                    let el = self.inner.unchecked_ref::<web_sys::Element>();
+                    let patch = patch.resolve(|k| el.has_attribute(k).then_some(true));
+                    self.update(Update::BooleanAttribute(patch))?;
+                }
             },
             Update::Style(patch) => {
                 let style = self
@@ -287,10 +581,17 @@ impl JsDom {
                             .remove_property(&k)
                             .map_err(|_| anyhow::anyhow!("could not remove style"))?;
                     }
+                    patch @ HashPatch::Modify { .. } => {
+                        let patch = patch.resolve(|k| {
+                            style.get_property_value(k).ok().filter(|v| !v.is_empty())
+                        });
+                        self.update(Update::Style(patch))?;
+                    }
                 }
             }
             Update::Child(patch) => {
-                let patch: ListPatch<JsDom> = patch.try_map(JsDom::try_from)?;
+                let patch: ListPatch<JsDom> = patch
+                    .try_map(|builder| JsDom::try_from(super::ensure_not_fragment(builder)?))?;
                 let _ = self.patch(patch);
             }
         }
@@ -329,6 +630,8 @@ impl JsDom {
             listener_callbacks: Default::default(),
             children: Default::default(),
             parents_children: None,
+            #[cfg(debug_assertions)]
+            drop_log: Default::default(),
         })
     }
 
@@ -345,6 +648,26 @@ impl JsDom {
             listener_callbacks: Default::default(),
             children: Default::default(),
             parents_children: None,
+            #[cfg(debug_assertions)]
+            drop_log: Default::default(),
+        })
+    }
+
+    /// Create a document fragment.
+    ///
+    /// Once the fragment is inserted into the document its children are moved
+    /// into the fragment's new parent, leaving the fragment itself empty.
+    pub fn fragment() -> anyhow::Result<Self> {
+        let fragment = DOCUMENT.with(|d| d.create_document_fragment());
+        let node: JsValue = fragment.into();
+        Ok(JsDom {
+            inner: SendWrapper::new(node),
+            update_handle: Default::default(),
+            listener_callbacks: Default::default(),
+            children: Default::default(),
+            parents_children: None,
+            #[cfg(debug_assertions)]
+            drop_log: Default::default(),
         })
     }
 
@@ -368,6 +691,134 @@ impl JsDom {
         Some(f(el))
     }
 
+    /// Dispatch a bubbling [`web_sys::CustomEvent`] with the given name and
+    /// detail payload from this node.
+    ///
+    /// This is useful for loosely coupled messaging between components, as
+    /// well as interop with non-mogwai code on the page.
+    pub fn dispatch_custom_event(&self, name: &str, detail: JsValue) -> anyhow::Result<()> {
+        let target = self
+            .clone_as::<web_sys::EventTarget>()
+            .context("not an event target")?;
+        let init = web_sys::CustomEventInit::new();
+        init.set_bubbles(true);
+        init.set_detail(&detail);
+        let event = web_sys::CustomEvent::new_with_event_init_dict(name, &init)
+            .map_err(|e| anyhow::anyhow!("could not create custom event: {:#?}", e))?;
+        target
+            .dispatch_event(&event)
+            .map_err(|e| anyhow::anyhow!("could not dispatch custom event: {:#?}", e))?;
+        Ok(())
+    }
+
+    /// Listen for [`web_sys::CustomEvent`]s of the given name on this node,
+    /// producing a stream of their detail payloads.
+    ///
+    /// ## Panics
+    /// Panics if this node is not an event target.
+    pub fn on_custom_event(&self, name: &'static str) -> impl Stream<Item = JsValue> + Send {
+        let target = self
+            .clone_as::<web_sys::EventTarget>()
+            .expect("not an event target");
+        crate::event::event_stream(name, &target).filter_map(|ev| {
+            ev.clone_as::<web_sys::CustomEvent>()
+                .map(|custom| custom.detail())
+        })
+    }
+
+    /// Listen for `input` events on this node, producing a stream of the
+    /// node's text content after each change.
+    ///
+    /// This is useful for reading the text of `contenteditable` elements.
+    ///
+    /// ## Panics
+    /// Panics if this node is not an event target.
+    pub fn on_content_change(&self) -> impl Stream<Item = String> + Send {
+        let target = self
+            .clone_as::<web_sys::EventTarget>()
+            .expect("not an event target");
+        let node = self.clone();
+        crate::event::event_stream("input", &target).map(move |_| {
+            node.visit_as(|node: &web_sys::Node| node.text_content().unwrap_or_default())
+                .unwrap_or_default()
+        })
+    }
+
+    /// Listen for `change` events on this `<input type="file">` node,
+    /// producing a stream of the selected files after each change.
+    ///
+    /// ## Panics
+    /// Panics if this node is not an input element.
+    pub fn on_file_select(&self) -> impl Stream<Item = Vec<SelectedFile>> + Send {
+        let input = self
+            .clone_as::<web_sys::HtmlInputElement>()
+            .expect("not an input element");
+        let node = self.clone();
+        crate::event::event_stream("change", &input).map(move |_| {
+            node.visit_as(selected_files).unwrap_or_default()
+        })
+    }
+
+    /// Observe this element's intersection with the viewport, producing a
+    /// stream of whether it intersects each time that changes.
+    ///
+    /// `root_margin` grows the viewport used for the intersection (or shrinks
+    /// it, if negative), in CSS margin syntax. Eg. `"200px"` reports the
+    /// element as intersecting when it is within 200 pixels of the viewport.
+    /// The observer is disconnected when the stream is dropped.
+    ///
+    /// ## Panics
+    /// Panics if this node is not an element.
+    pub fn on_intersect(&self, root_margin: &str) -> impl Stream<Item = bool> + Send {
+        let element = self
+            .clone_as::<web_sys::Element>()
+            .expect("not an element");
+        let (tx, rx) = mpsc::unbounded::<bool>();
+        let callback = Closure::wrap(Box::new(move |entries: js_sys::Array| {
+            for entry in entries.iter() {
+                let entry: web_sys::IntersectionObserverEntry = entry.unchecked_into();
+                let _ = tx.try_send(entry.is_intersecting());
+            }
+        }) as Box<dyn FnMut(js_sys::Array)>);
+        let init = web_sys::IntersectionObserverInit::new();
+        init.set_root_margin(root_margin);
+        let observer = web_sys::IntersectionObserver::new_with_options(
+            callback.as_ref().unchecked_ref(),
+            &init,
+        )
+        .expect("could not create an intersection observer");
+        observer.observe(&element);
+
+        struct Intersections {
+            observer: SendWrapper<web_sys::IntersectionObserver>,
+            _callback: SendWrapper<Closure<dyn FnMut(js_sys::Array)>>,
+            rx: mpsc::Receiver<bool>,
+        }
+
+        impl Drop for Intersections {
+            fn drop(&mut self) {
+                self.observer.disconnect();
+            }
+        }
+
+        impl Stream for Intersections {
+            type Item = bool;
+
+            fn poll_next(
+                self: Pin<&mut Self>,
+                cx: &mut std::task::Context<'_>,
+            ) -> std::task::Poll<Option<Self::Item>> {
+                Pin::new(&mut self.get_mut().rx).poll_next(cx)
+            }
+        }
+
+        Intersections {
+            observer: SendWrapper::new(observer),
+            _callback: SendWrapper::new(callback),
+            rx,
+        }
+    }
+
     ///// Attempt to get an attribute value.
     //pub fn get_attribute(&self, key: &str) -> Result<Option<String>, String> {
     //    match self.inner_read() {
@@ -388,22 +839,35 @@ impl JsDom {
     //    }
     //}
 
-    /// Return a string representation of the DOM tree.
+    /// Return the `outerHTML` of an element (or the data of a text node)
+    /// immediately, without awaiting.
     ///
-    /// ## Panics
-    /// Panics if the node cannot be turned into a string representation
-    pub async fn html_string(&self) -> String {
+    /// This is a snapshot of the DOM as it is right now - updates from
+    /// streams that have not yet been polled will not be reflected.
+    ///
+    /// Returns `None` if the node is neither an element nor a text node.
+    pub fn outer_html_sync(&self) -> Option<String> {
         if let Some(element) = self.inner.dyn_ref::<web_sys::Element>() {
-            return element.outer_html();
+            return Some(element.outer_html());
         }
 
         if let Some(text) = self.inner.dyn_ref::<web_sys::Text>() {
-            return text.data();
+            return Some(text.data());
         }
-        panic!(
-            "Dom reference {:#?} could not be turned into a string",
-            self.inner
-        );
+        None
+    }
+
+    /// Return a string representation of the DOM tree.
+    ///
+    /// ## Panics
+    /// Panics if the node cannot be turned into a string representation
+    pub async fn html_string(&self) -> String {
+        self.outer_html_sync().unwrap_or_else(|| {
+            panic!(
+                "Dom reference {:#?} could not be turned into a string",
+                self.inner
+            )
+        })
         //Either::Right(ssr) => ssr.html_string().await,
     }
 
@@ -427,6 +891,171 @@ impl JsDom {
         removed
     }
 
+    /// Replace all of this node's children with views built from the given
+    /// builders, using a single call to `replaceChildren`.
+    pub fn replace_children(&self, new: Vec<ViewBuilder>) -> anyhow::Result<()> {
+        let mut new = new
+            .into_iter()
+            .map(JsDom::try_from)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let nodes = new
+            .iter()
+            .map(|js| &*js.inner)
+            .collect::<js_sys::Array>();
+        self.visit_as(|el: &web_sys::Element| el.replace_children_with_node(&nodes))
+            .context("not an element")?;
+
+        let weakly_shared_children = Some(self.children.downgrade());
+        for child in new.iter_mut() {
+            child.parents_children = weakly_shared_children.clone();
+        }
+        let mut removed = std::mem::replace(
+            &mut *self.children.try_write().context("cannot write children")?,
+            new,
+        );
+        for removed_child in removed.iter_mut() {
+            removed_child.parents_children = None;
+        }
+        Ok(())
+    }
+
+    /// Replace this element's children with the given markup, using the
+    /// browser's `Element.innerHTML`.
+    ///
+    /// ## Warning
+    /// The markup is not sanitized. Only use this with trusted markup.
+    ///
+    /// Fails if this is not an element.
+    pub fn set_inner_html(&self, html: &str) -> anyhow::Result<()> {
+        self.visit_as(|el: &web_sys::Element| el.set_inner_html(html))
+            .context("not an element")?;
+        let mut removed =
+            std::mem::take(&mut *self.children.try_write().context("cannot write children")?);
+        for removed_child in removed.iter_mut() {
+            removed_child.parents_children = None;
+        }
+        Ok(())
+    }
+
+    /// Insert `other` at the given position relative to this element, using
+    /// the browser's `Element.insertAdjacentElement`.
+    ///
+    /// When `other` ends up inside a parent built by mogwai, that parent
+    /// takes ownership of it, just as if it had been added with a child
+    /// patch. Otherwise it is up to the caller to keep `other` alive. If
+    /// `other` already belonged to a parent built by mogwai, that parent
+    /// gives it up.
+    ///
+    /// Fails if either node is not an element, or if this element has no
+    /// parent and `position` is [`AdjacentPosition::BeforeBegin`] or
+    /// [`AdjacentPosition::AfterEnd`].
+    pub fn insert_adjacent(&self, position: AdjacentPosition, other: &JsDom) -> anyhow::Result<()> {
+        let el = self
+            .inner
+            .dyn_ref::<web_sys::Element>()
+            .context("not an element")?;
+        let other_el = other
+            .inner
+            .dyn_ref::<web_sys::Element>()
+            .context("inserted node is not an element")?;
+        let inserted = el
+            .insert_adjacent_element(position.as_str(), other_el)
+            .map_err(|e| anyhow::anyhow!("could not insert adjacent element: {:?}", e))?;
+        anyhow::ensure!(inserted.is_some(), "element has no parent to insert into");
+
+        // the browser moved the node, so its old mogwai parent gives it up
+        let mut other = other.clone();
+        if let Some(old_siblings) = other.parents_children.take().and_then(|w| w.upgrade()) {
+            old_siblings
+                .try_write()
+                .context("cannot write children")?
+                .retain(|s| *s.inner != *other.inner);
+        }
+        let (siblings, index) = match position {
+            AdjacentPosition::AfterBegin => (Some(self.children.clone()), Some(0)),
+            AdjacentPosition::BeforeEnd => (Some(self.children.clone()), None),
+            AdjacentPosition::BeforeBegin | AdjacentPosition::AfterEnd => {
+                let siblings = self.parents_children.as_ref().and_then(|w| w.upgrade());
+                let index = siblings.as_ref().and_then(|siblings| {
+                    let siblings = siblings.try_read()?;
+                    let i = siblings.iter().position(|s| *s.inner == *self.inner)?;
+                    Some(if position == AdjacentPosition::AfterEnd {
+                        i + 1
+                    } else {
+                        i
+                    })
+                });
+                (siblings, index)
+            }
+        };
+        if let Some(siblings) = siblings {
+            other.parents_children = Some(siblings.downgrade());
+            let mut siblings = siblings.try_write().context("cannot write children")?;
+            let index = index.unwrap_or(siblings.len()).min(siblings.len());
+            siblings.insert(index, other);
+        }
+        Ok(())
+    }
+
+    /// Set a property of this node, eg. the `value` of an input.
+    ///
+    /// Unlike attributes, properties reflect the live state of the node.
+    pub fn set_property(&self, name: &str, value: &JsValue) -> anyhow::Result<()> {
+        js_sys::Reflect::set(&self.inner, &JsValue::from_str(name), value)
+            .map_err(|_| anyhow::anyhow!("could not set property '{}'", name))?;
+        Ok(())
+    }
+
+    /// Give this element keyboard focus.
+    ///
+    /// This is a no-op when not running on WASM.
+    ///
+    /// Fails if this is not an HTML element.
+    pub fn focus(&self) -> anyhow::Result<()> {
+        if !cfg!(target_arch = "wasm32") {
+            return Ok(());
+        }
+        self.visit_as(|el: &web_sys::HtmlElement| el.focus())
+            .context("not an html element")?
+            .map_err(|e| anyhow::anyhow!("could not focus: {:?}", e))
+    }
+
+    /// Remove keyboard focus from this element.
+    ///
+    /// This is a no-op when not running on WASM.
+    ///
+    /// Fails if this is not an HTML element.
+    pub fn blur(&self) -> anyhow::Result<()> {
+        if !cfg!(target_arch = "wasm32") {
+            return Ok(());
+        }
+        self.visit_as(|el: &web_sys::HtmlElement| el.blur())
+            .context("not an html element")?
+            .map_err(|e| anyhow::anyhow!("could not blur: {:?}", e))
+    }
+
+    /// Scroll this element's ancestors so that it is visible, optionally
+    /// animating the scroll.
+    ///
+    /// This is a no-op when not running on WASM.
+    ///
+    /// Fails if this is not an element.
+    pub fn scroll_into_view(&self, smooth: bool) -> anyhow::Result<()> {
+        if !cfg!(target_arch = "wasm32") {
+            return Ok(());
+        }
+        let options = web_sys::ScrollIntoViewOptions::new();
+        options.set_behavior(if smooth {
+            web_sys::ScrollBehavior::Smooth
+        } else {
+            web_sys::ScrollBehavior::Auto
+        });
+        self.visit_as(|el: &web_sys::Element| {
+            el.scroll_into_view_with_scroll_into_view_options(&options)
+        })
+        .context("not an element")
+    }
+
     /// Run this view in a parent container forever, never dropping it.
     pub fn run_in_container(self, container: JsDom) -> anyhow::Result<()> {
         container.patch(ListPatch::push(self));
@@ -457,6 +1086,17 @@ impl JsDom {
         Ok(t)
     }
 
+    /// Run `fut` until it completes, or until `millis` milliseconds pass.
+    ///
+    /// Returns an error if the deadline passes first.
+    pub async fn run_while_timeout<T: 'static>(
+        &self,
+        millis: u64,
+        fut: impl Future<Output = T> + 'static,
+    ) -> anyhow::Result<T> {
+        self.run_while(super::timeout(millis, fut)).await?
+    }
+
     pub fn add_listener_with(
         &self,
         Listener {
@@ -567,6 +1207,59 @@ pub(crate) fn list_patch_apply_node(
                 removed.push(child);
             }
         }
+        ListPatch::Replace { index, value } => {
+            if let Some(old_child) = self_node.child_nodes().get(index as u32) {
+                self_node.replace_child(&value, &old_child).unwrap_throw();
+                removed.push(old_child);
+            }
+        }
+        ListPatch::Move { from, to } => {
+            let list: web_sys::NodeList = self_node.child_nodes();
+            let children: Vec<web_sys::Node> =
+                (0..list.length()).filter_map(|i| list.get(i)).collect();
+            if let (Some(moving), true) = (children.get(from), to < children.len()) {
+                let has_focus = DOCUMENT
+                    .with(|d| d.active_element())
+                    .map(|el| moving.contains(Some(&el)))
+                    .unwrap_or_default();
+                if has_focus {
+                    // moving a node that contains the focused element would
+                    // blur it, so move the nodes between instead
+                    if from < to {
+                        for child in &children[from + 1..=to] {
+                            self_node.insert_before(child, Some(moving)).unwrap_throw();
+                        }
+                    } else {
+                        let after = moving.next_sibling();
+                        for child in &children[to..from] {
+                            self_node.insert_before(child, after.as_ref()).unwrap_throw();
+                        }
+                    }
+                } else {
+                    let before = children.iter().filter(|child| *child != moving).nth(to);
+                    self_node.insert_before(moving, before).unwrap_throw();
+                }
+            }
+        }
+        ListPatch::Batch(patches) => {
+            if patches
+                .iter()
+                .all(|patch| matches!(patch, ListPatch::Push(_)))
+            {
+                // append all the pushed nodes at once
+                let fragment = DOCUMENT.with(|d| d.create_document_fragment());
+                for patch in patches.into_iter() {
+                    if let ListPatch::Push(new_node) = patch {
+                        let _ = fragment.append_child(&new_node).unwrap_throw();
+                    }
+                }
+                let _ = self_node.append_child(&fragment).unwrap_throw();
+            } else {
+                for patch in patches.into_iter() {
+                    removed.extend(list_patch_apply_node(self_node, patch));
+                }
+            }
+        }
         ListPatch::Noop => {}
     }
     removed
@@ -596,6 +1289,10 @@ pub(crate) fn build(
     } = builder;
     let hydrating_root = hydration_root.is_some();
     let hydrating_child = may_parent.is_some();
+    let tag = match &identity {
+        ViewIdentity::Branch(t) | ViewIdentity::NamespacedBranch(t, _) => Some(t.to_string()),
+        ViewIdentity::Leaf(_) | ViewIdentity::Fragment => None,
+    };
 
     // intialize it
     let mut dom = if hydrating_child {
@@ -607,6 +1304,7 @@ pub(crate) fn build(
             ViewIdentity::Branch(t) => HydrationKey::try_new(t, attribs, may_parent),
             ViewIdentity::NamespacedBranch(t, _) => HydrationKey::try_new(t, attribs, may_parent),
             ViewIdentity::Leaf(t) => HydrationKey::try_new(t, attribs, may_parent),
+            ViewIdentity::Fragment => Err(anyhow::anyhow!("a fragment cannot be hydrated")),
         }?;
         key.hydrate()?
     } else {
@@ -617,11 +1315,20 @@ pub(crate) fn build(
                 ViewIdentity::Branch(tag) => JsDom::element(&tag, None),
                 ViewIdentity::NamespacedBranch(tag, ns) => JsDom::element(&tag, Some(&ns)),
                 ViewIdentity::Leaf(text) => JsDom::text(&text),
+                ViewIdentity::Fragment => JsDom::fragment(),
             }?
         }
     };
 
     if hydrating_root || hydrating_child {
+        if let (Some(tag), Some(el)) = (tag, dom.clone_as::<web_sys::Element>()) {
+            let attribs = initial_values.iter().filter_map(|update| match update {
+                Update::Attribute(patch) => Some(patch.clone()),
+                _ => None,
+            });
+            check_hydrated_attributes(&tag, attribs, |name| el.get_attribute(name))?;
+        }
+
         let child_patches = initial_values
             .into_iter()
             .filter_map(|update| match update {
@@ -715,8 +1422,14 @@ impl From<JsDom> for ViewBuilder {
 }
 
 /// Used to identify an existing node when hydrating `JsDom`.
+///
+/// Nodes are identified by their `id` first, then by the `data-mogwai-key`
+/// set with [`ViewBuilder::with_key`], and last by their index among their
+/// siblings.
 pub enum HydrationKey {
     Id(String),
+    Key(String),
+    KeyedChildOf { node: web_sys::Node, key: String },
     IndexedChildOf { node: web_sys::Node, index: u32 },
 }
 
@@ -735,6 +1448,16 @@ impl HydrationKey {
             return Ok(HydrationKey::Id(id));
         }
 
+        if let Some(key) = attributes.remove("data-mogwai-key") {
+            return Ok(match may_parent {
+                Some((_, parent)) => HydrationKey::KeyedChildOf {
+                    node: parent.clone(),
+                    key,
+                },
+                None => HydrationKey::Key(key),
+            });
+        }
+
         if let Some((index, parent)) = may_parent {
             return Ok(HydrationKey::IndexedChildOf {
                 node: parent.clone(),
@@ -744,7 +1467,7 @@ impl HydrationKey {
 
         anyhow::bail!(
             "Missing any hydration option for node '{}' - must be the child of a node or have an \
-             id",
+             id or key",
             tag.as_ref()
         )
     }
@@ -771,6 +1494,33 @@ impl HydrationKey {
                     )
                 })?
             }
+            HydrationKey::Key(key) => crate::utils::document()
+                .clone_as::<web_sys::Document>()
+                .with_context(|| "wasm only")?
+                .query_selector(&format!(r#"[data-mogwai-key="{}"]"#, key))
+                .ok()
+                .flatten()
+                .with_context(|| format!("Could not find an element with key '{}'", key))?
+                .unchecked_into(),
+            HydrationKey::KeyedChildOf { node, key } => {
+                let children = node.child_nodes();
+                (0..children.length())
+                    .filter_map(|i| children.get(i))
+                    .find(|child| {
+                        child
+                            .dyn_ref::<web_sys::Element>()
+                            .and_then(|el| el.get_attribute("data-mogwai-key"))
+                            .as_deref()
+                            == Some(key.as_str())
+                    })
+                    .with_context(|| {
+                        format!(
+                            "Child with key '{}' could not be found in node '{}'",
+                            key,
+                            node.node_name()
+                        )
+                    })?
+            }
             HydrationKey::IndexedChildOf { node, index } => {
                 let children = node.child_nodes();
                 let mut non_empty_children = vec![];
@@ -823,6 +1573,71 @@ impl HydrationKey {
     }
 }
 
+/// An error found while hydrating [`JsDom`] from pre-built DOM.
+///
+/// These are returned wrapped in an [`anyhow::Error`], from which they can be
+/// recovered with [`anyhow::Error::downcast_ref`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HydrationError {
+    /// An existing node's attribute differs from the builder's initial value.
+    AttributeMismatch {
+        /// Tag name of the node.
+        tag: String,
+        /// Name of the attribute.
+        name: String,
+        /// The builder's initial value.
+        expected: String,
+        /// The existing node's value, if it has the attribute.
+        found: Option<String>,
+    },
+}
+
+impl std::fmt::Display for HydrationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HydrationError::AttributeMismatch {
+                tag,
+                name,
+                expected,
+                found,
+            } => write!(
+                f,
+                "hydrated '{}' has {} attribute {:?}, but the view expected {:?}",
+                tag, name, found, expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for HydrationError {}
+
+/// Compare the builder's initial (non-streaming) attribute values against
+/// those found on an existing node.
+pub(crate) fn check_hydrated_attributes(
+    tag: &str,
+    attribs: impl Iterator<Item = HashPatch<String, String>>,
+    found: impl Fn(&str) -> Option<String>,
+) -> Result<(), HydrationError> {
+    let mut attributes = HashMap::new();
+    for patch in attribs {
+        let _ = attributes.hash_patch_apply(patch);
+    }
+    let mut attributes = attributes.into_iter().collect::<Vec<_>>();
+    attributes.sort();
+    for (name, expected) in attributes.into_iter() {
+        let found = found(&name);
+        if found.as_ref() != Some(&expected) {
+            return Err(HydrationError::AttributeMismatch {
+                tag: tag.to_string(),
+                name,
+                expected,
+                found,
+            });
+        }
+    }
+    Ok(())
+}
+
 /// Used to "hydrate" a `JsDom` from a ViewBuilder and pre-built DOM.
 ///
 /// We use this when creating `JsDom` from DOM that was pre-rendered
@@ -854,6 +1669,7 @@ impl TryFrom<ViewBuilder> for Hydrator {
                 ViewIdentity::Branch(t) => HydrationKey::try_new(t, attribs, None),
                 ViewIdentity::NamespacedBranch(t, _) => HydrationKey::try_new(t, attribs, None),
                 ViewIdentity::Leaf(t) => HydrationKey::try_new(t, attribs, None),
+                ViewIdentity::Fragment => Err(anyhow::anyhow!("a fragment cannot be hydrated")),
             }?;
             builder.hydration_root = Some(AnyView::new(key.hydrate()?));
         }
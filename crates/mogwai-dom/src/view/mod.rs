@@ -9,22 +9,43 @@ use mogwai::{
     view::{AnyEvent, AnyView, Downcast, Listener, Update, ViewBuilder},
 };
 pub use serde_json::Value;
-pub use ssr::SsrDom;
-use wasm_bindgen::JsCast;
+pub use ssr::{SsrDom, SsrDomEvent, ViewDiff};
+use wasm_bindgen::{JsCast, JsValue};
 
 pub use crate::event::JsDomEvent;
 
 pub(crate) mod atomic;
 
 pub mod js;
-pub use js::JsDom;
-
-use self::ssr::SsrDomEvent;
+pub use js::{AdjacentPosition, DebugOnDrop, JsDom};
 
 mod ssr;
 
 pub(crate) struct FutureTask<T>(pub(crate) Pin<Box<dyn Future<Output = T> + Send>>);
 
+/// Race `fut` against a timer, resolving to an error if `millis` milliseconds
+/// pass before `fut` completes.
+pub(crate) async fn timeout<T>(millis: u64, fut: impl Future<Output = T>) -> anyhow::Result<T> {
+    futures_lite::FutureExt::or(async { Ok(fut.await) }, async {
+        let _ = mogwai::time::wait_millis(millis).await;
+        Err(anyhow::anyhow!("future did not finish within {}ms", millis))
+    })
+    .await
+}
+
+/// Reject fragments in child patches.
+///
+/// A fragment in a patch would be one item of the patched list but many
+/// nodes in the view, so the indices of later patches would target the wrong
+/// nodes. Fragments can still be appended, see [`ViewBuilder::fragment`].
+pub(crate) fn ensure_not_fragment(builder: ViewBuilder) -> anyhow::Result<ViewBuilder> {
+    anyhow::ensure!(
+        !builder.is_fragment(),
+        "a fragment cannot be patched in as a child, append it or patch in its children instead"
+    );
+    Ok(builder)
+}
+
 #[derive(Clone)]
 pub struct Dom(Either<JsDom, SsrDom>);
 
@@ -96,13 +117,90 @@ impl Dom {
         js.clone_as::<T>()
     }
 
+    /// Returns this node's parent, if it has one.
+    pub fn parent(&self) -> Option<Dom> {
+        match self.as_either_ref() {
+            Either::Left(js) => js.parent().map(Dom::from),
+            Either::Right(ssr) => ssr.parent().map(Dom::from),
+        }
+    }
+
+    /// Returns this node's children in order, including text nodes.
+    pub fn children(&self) -> Vec<Dom> {
+        match self.as_either_ref() {
+            Either::Left(js) => js.children().into_iter().map(Dom::from).collect(),
+            Either::Right(ssr) => ssr.children().into_iter().map(Dom::from).collect(),
+        }
+    }
+
+    /// Remove this node from its parent, if it has one.
     pub fn detach(&self) -> anyhow::Result<()> {
-        let js: &JsDom = self
-            .as_either_ref()
-            .left()
-            .context("cannot detach an SsrDom yet")?;
-        js.detach();
-        Ok(())
+        match self.as_either_ref() {
+            Either::Left(js) => {
+                js.detach();
+                Ok(())
+            }
+            Either::Right(ssr) => ssr.detach(),
+        }
+    }
+
+    /// Replace all of this node's children with views built from the given
+    /// builders at once.
+    ///
+    /// This is cheaper than removing and adding children with separate
+    /// patches.
+    pub fn replace_children(&self, new: Vec<ViewBuilder>) -> anyhow::Result<()> {
+        match self.as_either_ref() {
+            Either::Left(js) => js.replace_children(new),
+            Either::Right(ssr) => ssr.replace_children(new),
+        }
+    }
+
+    /// Replace this element's children with the given markup.
+    ///
+    /// ## Warning
+    /// The markup is not sanitized. Only use this with trusted markup.
+    pub fn set_inner_html(&self, html: &str) -> anyhow::Result<()> {
+        match self.as_either_ref() {
+            Either::Left(js) => js.set_inner_html(html),
+            Either::Right(ssr) => ssr.set_inner_html(html),
+        }
+    }
+
+    /// Insert `other` at the given position relative to this element.
+    ///
+    /// See [`JsDom::insert_adjacent`]. This is not yet supported server-side.
+    pub fn insert_adjacent(&self, position: AdjacentPosition, other: &Dom) -> anyhow::Result<()> {
+        match (self.as_either_ref(), other.as_either_ref()) {
+            (Either::Left(js), Either::Left(other)) => js.insert_adjacent(position, other),
+            (Either::Left(_), Either::Right(_)) | (Either::Right(_), Either::Left(_)) => {
+                anyhow::bail!("cannot mix browser and server-side nodes")
+            }
+            (Either::Right(_), Either::Right(_)) => {
+                anyhow::bail!("insert_adjacent is not supported server-side")
+            }
+        }
+    }
+
+    /// Set a property of this element, eg. the `value` of an input.
+    ///
+    /// In the browser this sets the live property of the node. Server-side it
+    /// sets (or removes) the attribute of the same name, see
+    /// [`SsrDom::set_property`].
+    pub fn set_property(&self, name: &str, value: impl Into<Value>) -> anyhow::Result<()> {
+        match self.as_either_ref() {
+            Either::Left(js) => {
+                let value = match value.into() {
+                    Value::String(s) => JsValue::from_str(&s),
+                    Value::Bool(b) => JsValue::from_bool(b),
+                    Value::Number(n) => JsValue::from_f64(n.as_f64().unwrap_or_default()),
+                    Value::Null => JsValue::NULL,
+                    value => anyhow::bail!("unsupported property value: {}", value),
+                };
+                js.set_property(name, &value)
+            }
+            Either::Right(ssr) => ssr.set_property(name, value.into()),
+        }
     }
 
     pub async fn html_string(&self) -> String {
@@ -122,18 +220,54 @@ impl Dom {
         }
     }
 
+    /// Run this view's tasks until `fut` completes, or until `millis`
+    /// milliseconds pass, whichever comes first.
+    ///
+    /// Returns an error if the deadline passes before `fut` completes.
+    pub async fn run_while_timeout<T: 'static>(
+        &self,
+        millis: u64,
+        fut: impl Future<Output = T> + 'static,
+    ) -> anyhow::Result<T> {
+        match self.as_either_ref() {
+            Either::Left(js) => js.run_while_timeout(millis, fut).await,
+            Either::Right(ssr) => ssr.run_while_timeout(millis, fut).await,
+        }
+    }
+
     /// Run this element forever.
     ///
     /// ## Note
     /// * On WASM this hands ownership over to Javascript (in the browser
     ///   window)
     /// * On other targets this loops forever, running the server-side rendered
-    ///   node's async tasks.
+    ///   node's async tasks. See [`Dom::run_with_interval`].
     pub fn run(self) -> anyhow::Result<()> {
+        self.run_with_interval(1)
+    }
+
+    /// Run this element forever, sleeping for `millis` milliseconds between
+    /// ticks of the server-side rendered node's executor whenever it has
+    /// nothing to do.
+    ///
+    /// This keeps long running server-side renderers from spinning a CPU core
+    /// while idle, at the cost of up to `millis` of latency. On WASM this is
+    /// the same as [`Dom::run`].
+    ///
+    /// ```rust, no_run
+    /// use mogwai_dom::prelude::*;
+    ///
+    /// let dom = Dom::try_from(rsx! { p() {"Hello"} }).unwrap();
+    /// // wake up at most every 10ms when idle
+    /// dom.run_with_interval(10).unwrap();
+    /// ```
+    pub fn run_with_interval(self, millis: u64) -> anyhow::Result<()> {
         match self.0 {
             Either::Left(js) => js.run(),
             Either::Right(ssr) => loop {
-                let _ = ssr.executor.try_tick();
+                if !ssr.executor.try_tick() {
+                    std::thread::sleep(std::time::Duration::from_millis(millis));
+                }
             },
         }
     }
@@ -143,7 +277,7 @@ impl Dom {
             Update::Child(patch) => {
                 let patch: ListPatch<Dom> =
                     patch.try_map(|builder: ViewBuilder| -> anyhow::Result<Dom> {
-                        Dom::new(self.executor().cloned(), builder)
+                        Dom::new(self.executor().cloned(), ensure_not_fragment(builder)?)
                     })?;
                 match self.clone().as_either_mut() {
                     Either::Left(js) => {
@@ -205,6 +339,48 @@ impl DomEvent {
             Either::Right(val) => Either::Right(val),
         }
     }
+
+    /// Borrow the browser event as `T`, eg. a `web_sys::KeyboardEvent`,
+    /// without cloning or consuming it.
+    ///
+    /// Returns `None` server-side, or if the event is not a `T`.
+    pub fn downcast_ref<T: JsCast>(&self) -> Option<&T> {
+        self.as_either_ref().left()?.downcast_ref::<T>()
+    }
+
+    /// The pointer's position, if this is a pointer event.
+    ///
+    /// In the browser this is the position of a mouse or pointer event in the
+    /// viewport. Server-side this is read from the `clientX` and `clientY`
+    /// fields of the event's value.
+    pub fn pointer_position(&self) -> Option<(f64, f64)> {
+        match self.as_either_ref() {
+            Either::Left(js) => js.pointer_position(),
+            Either::Right(ssr) => ssr.pointer_position(),
+        }
+    }
+
+    /// The `value` of this event's target, eg. the text of an `input`.
+    ///
+    /// Server-side this is read from the `target.value` field of the event's
+    /// value. This is what `on:<event>(value)` in `rsx!` delivers.
+    pub fn target_value(&self) -> Option<String> {
+        match self.as_either_ref() {
+            Either::Left(js) => js.target_value(),
+            Either::Right(ssr) => ssr.target_value(),
+        }
+    }
+
+    /// The boolean `property` of this event's target, eg. `checked`.
+    ///
+    /// Server-side this is read from the `target.<property>` field of the
+    /// event's value. This is what `bind:checked` in `rsx!` delivers.
+    pub fn target_bool(&self, property: &str) -> Option<bool> {
+        match self.as_either_ref() {
+            Either::Left(js) => js.target_bool(property),
+            Either::Right(ssr) => ssr.target_bool(property),
+        }
+    }
 }
 
 impl std::fmt::Debug for DomEvent {
@@ -261,4 +437,70 @@ mod test {
             vals
         );
     }
+
+    #[test]
+    fn ssr_replace_children() {
+        use mogwai::view::ViewBuilder;
+
+        let executor = std::sync::Arc::new(async_executor::Executor::default());
+        let dom = super::Dom::new(
+            Some(executor),
+            ViewBuilder::element("ul")
+                .append(ViewBuilder::element("li").append("a"))
+                .append(ViewBuilder::element("li").append("b")),
+        )
+        .unwrap();
+        dom.replace_children(vec![
+            ViewBuilder::element("li").append("c"),
+            ViewBuilder::element("li").append("d"),
+            ViewBuilder::element("li").append("e"),
+        ])
+        .unwrap();
+        assert_eq!(
+            mogwai::future::block_on(dom.html_string()),
+            "<ul><li>c</li> <li>d</li> <li>e</li></ul>"
+        );
+    }
+
+    #[test]
+    fn ssr_event_pointer_position() {
+        let click = super::DomEvent(mogwai::either::Either::Right(super::SsrDomEvent(
+            serde_json::json!({"clientX": 12.0, "clientY": 34}),
+        )));
+        assert_eq!(click.pointer_position(), Some((12.0, 34.0)));
+
+        let keyup = super::DomEvent(mogwai::either::Either::Right(super::SsrDomEvent(
+            serde_json::json!({"key": "Enter"}),
+        )));
+        assert_eq!(keyup.pointer_position(), None);
+    }
+
+    #[test]
+    fn ssr_event_target_value() {
+        let input = super::DomEvent(mogwai::either::Either::Right(super::SsrDomEvent(
+            serde_json::json!({"target": {"value": "hello"}}),
+        )));
+        assert_eq!(input.target_value().as_deref(), Some("hello"));
+
+        let click = super::DomEvent(mogwai::either::Either::Right(super::SsrDomEvent(
+            serde_json::json!({"clientX": 12.0, "clientY": 34}),
+        )));
+        assert_eq!(click.target_value(), None);
+    }
+
+    #[test]
+    fn ssr_event_downcast_ref() {
+        use mogwai::view::Downcast;
+
+        let any = mogwai::view::AnyEvent::new(super::SsrDomEvent(
+            serde_json::json!({"clientX": 12.0, "clientY": 34}),
+        ));
+        let ssr = any.downcast_ref::<super::SsrDomEvent>().unwrap();
+        assert_eq!(ssr.pointer_position(), Some((12.0, 34.0)));
+
+        // the event was only borrowed
+        let event: super::DomEvent = any.downcast().unwrap();
+        assert_eq!(event.pointer_position(), Some((12.0, 34.0)));
+        assert!(event.downcast_ref::<web_sys::Event>().is_none());
+    }
 }
@@ -2,7 +2,14 @@
 use anyhow::Context;
 use async_executor::Executor;
 use async_lock::RwLock;
-use std::{borrow::Cow, collections::HashMap, future::Future, ops::DerefMut, pin::Pin, sync::Arc};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    future::Future,
+    ops::{Deref, DerefMut},
+    pin::Pin,
+    sync::{Arc, Weak},
+};
 
 use mogwai::{
     either::Either,
@@ -18,6 +25,29 @@ use super::FutureTask;
 #[derive(Clone, Debug)]
 pub struct SsrDomEvent(pub Value);
 
+impl SsrDomEvent {
+    /// The pointer's position, if this event's value is an object with
+    /// numeric `clientX` and `clientY` fields.
+    pub fn pointer_position(&self) -> Option<(f64, f64)> {
+        let x = self.0.get("clientX")?.as_f64()?;
+        let y = self.0.get("clientY")?.as_f64()?;
+        Some((x, y))
+    }
+
+    /// The target's value, if this event's value is an object with a string
+    /// `target.value` field.
+    pub fn target_value(&self) -> Option<String> {
+        let value = self.0.get("target")?.get("value")?.as_str()?;
+        Some(value.to_string())
+    }
+
+    /// The target's boolean `property`, if this event's value is an object
+    /// with a boolean `target.<property>` field.
+    pub fn target_bool(&self, property: &str) -> Option<bool> {
+        self.0.get("target")?.get(property)?.as_bool()
+    }
+}
+
 impl Downcast<SsrDomEvent> for AnyEvent {
     fn downcast(self) -> anyhow::Result<SsrDomEvent> {
         #[cfg(debug_assertions)]
@@ -80,6 +110,8 @@ fn tag_is_voidable(tag: &Cow<'static, str>) -> bool {
 pub enum SsrNode {
     /// Text node.
     Text(String),
+    /// Raw markup, written verbatim, eg. from [`SsrDom::set_inner_html`].
+    Html(String),
     /// Parent node.
     Container {
         /// Tag name.
@@ -94,37 +126,110 @@ pub enum SsrNode {
 }
 
 impl SsrNode {
-    pub async fn html_string(&self) -> String {
+    /// Returns the node's attributes with its styles merged into the `style`
+    /// attribute.
+    fn attributes_with_styles(
+        attributes: &[(String, Option<String>)],
+        styles: &[(String, String)],
+    ) -> Vec<(String, Option<String>)> {
+        let mut attributes = attributes.to_vec();
+        if !styles.is_empty() {
+            let styles = styles
+                .iter()
+                .map(|(k, v)| format!("{}: {};", k, v))
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            let mut style_added = false;
+            for (key, value) in attributes.iter_mut() {
+                if key == "style" {
+                    if let Some(prev_style) = value.as_mut() {
+                        *prev_style = vec![prev_style.as_str(), styles.as_str()].join(" ");
+                        style_added = true;
+                        break;
+                    }
+                }
+            }
+            if !style_added {
+                attributes.push(("style".into(), Some(styles)));
+            }
+        }
+        attributes
+    }
+
+    /// Write this node's HTML into `w`, reading children without waiting on
+    /// their locks.
+    ///
+    /// Produces the same output as [`SsrNode::html_string`].
+    fn render_into(&self, w: &mut impl std::fmt::Write) -> anyhow::Result<()> {
         match self {
-            SsrNode::Text(s) => s.to_string(),
+            SsrNode::Text(s) | SsrNode::Html(s) => w.write_str(s)?,
+            SsrNode::Container { name, children, .. } if name.is_empty() => {
+                for (i, kid) in children.iter().enumerate() {
+                    if i > 0 {
+                        w.write_str(" ")?;
+                    }
+                    kid.node
+                        .try_read()
+                        .context("cannot read child node")?
+                        .render_into(w)?;
+                }
+            }
             SsrNode::Container {
                 name,
                 attributes,
                 children,
                 styles,
             } => {
-                let mut attributes = attributes.clone();
-                if !styles.is_empty() {
-                    let styles = styles
-                        .iter()
-                        .map(|(k, v)| format!("{}: {};", k, v))
-                        .collect::<Vec<_>>()
-                        .join(" ");
-
-                    let mut style_added = false;
-                    for (key, value) in attributes.iter_mut() {
-                        if key == "style" {
-                            if let Some(prev_style) = value.as_mut() {
-                                *prev_style = vec![prev_style.as_str(), styles.as_str()].join(" ");
-                                style_added = true;
-                                break;
-                            }
-                        }
+                write!(w, "<{}", name)?;
+                for (key, may_val) in SsrNode::attributes_with_styles(attributes, styles) {
+                    if let Some(val) = may_val {
+                        write!(w, r#" {}="{}""#, key, val)?;
+                    } else {
+                        write!(w, " {}", key)?;
+                    }
+                }
+                if children.is_empty() {
+                    if tag_is_voidable(name) {
+                        w.write_str(" />")?;
+                    } else {
+                        write!(w, "></{}>", name)?;
                     }
-                    if !style_added {
-                        attributes.push(("style".into(), Some(styles)));
+                } else {
+                    w.write_str(">")?;
+                    for (i, kid) in children.iter().enumerate() {
+                        if i > 0 {
+                            w.write_str(" ")?;
+                        }
+                        kid.node
+                            .try_read()
+                            .context("cannot read child node")?
+                            .render_into(w)?;
                     }
+                    write!(w, "</{}>", name)?;
                 }
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn html_string(&self) -> String {
+        match self {
+            SsrNode::Text(s) | SsrNode::Html(s) => s.to_string(),
+            SsrNode::Container { name, children, .. } if name.is_empty() => {
+                let mut kids = vec![];
+                for kid in children.iter() {
+                    kids.push(kid.html_string().await);
+                }
+                kids.join(" ")
+            }
+            SsrNode::Container {
+                name,
+                attributes,
+                children,
+                styles,
+            } => {
+                let attributes = SsrNode::attributes_with_styles(attributes, styles);
 
                 let atts = attributes
                     .iter()
@@ -167,6 +272,160 @@ impl SsrNode {
             }
         }
     }
+
+    /// The HTML of this node with each element on its own line, indented by
+    /// `indent` spaces for each of its `depth` ancestors.
+    ///
+    /// Elements that only contain text are kept on one line.
+    async fn html_string_pretty(&self, indent: usize, depth: usize) -> String {
+        let pad = " ".repeat(indent * depth);
+        match self {
+            SsrNode::Container { name, children, .. } if name.is_empty() => {
+                let mut lines = vec![];
+                for kid in children.iter() {
+                    lines.push(kid.html_string_pretty_at(indent, depth).await);
+                }
+                lines.join("\n")
+            }
+            SsrNode::Container {
+                name,
+                attributes,
+                children,
+                styles,
+            } if !children.is_empty() => {
+                let mut only_text = true;
+                for kid in children.iter() {
+                    only_text &= matches!(&*kid.node.read().await, SsrNode::Text(_));
+                }
+                if only_text {
+                    return format!("{}{}", pad, self.html_string().await);
+                }
+
+                let mut lines = vec![];
+                let mut open = format!("{}<{}", pad, name);
+                for (key, may_val) in SsrNode::attributes_with_styles(attributes, styles) {
+                    if let Some(val) = may_val {
+                        open.push_str(&format!(r#" {}="{}""#, key, val));
+                    } else {
+                        open.push_str(&format!(" {}", key));
+                    }
+                }
+                open.push('>');
+                lines.push(open);
+                for kid in children.iter() {
+                    lines.push(kid.html_string_pretty_at(indent, depth + 1).await);
+                }
+                lines.push(format!("{}</{}>", pad, name));
+                lines.join("\n")
+            }
+            node => format!("{}{}", pad, node.html_string().await),
+        }
+    }
+}
+
+/// A difference between two [`SsrDom`] trees, as reported by [`SsrDom::diff`].
+///
+/// Each difference is located by its `path`, the child indices leading from
+/// the root of the trees to the differing node.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ViewDiff {
+    /// The nodes differ in kind or tag name.
+    ///
+    /// Elements are described by their tag name, text nodes by `#text`.
+    Node {
+        path: Vec<usize>,
+        left: String,
+        right: String,
+    },
+    /// An attribute differs, where `None` is an absent attribute.
+    ///
+    /// Styles are compared as part of the `style` attribute and boolean
+    /// attributes have empty values.
+    Attribute {
+        path: Vec<usize>,
+        name: String,
+        left: Option<String>,
+        right: Option<String>,
+    },
+    /// The text of text nodes differ.
+    Text {
+        path: Vec<usize>,
+        left: String,
+        right: String,
+    },
+    /// The elements have different numbers of children.
+    ///
+    /// Only the children both elements have are compared.
+    Children {
+        path: Vec<usize>,
+        left: usize,
+        right: usize,
+    },
+}
+
+/// A compound selector like `button#save.primary`, as supported by
+/// [`SsrDom::query_selector`].
+struct SimpleSelector<'a> {
+    tag: Option<&'a str>,
+    id: Option<&'a str>,
+    classes: Vec<&'a str>,
+}
+
+impl<'a> SimpleSelector<'a> {
+    fn parse(sel: &'a str) -> Option<Self> {
+        let sel = sel.trim();
+        let mut selector = SimpleSelector {
+            tag: None,
+            id: None,
+            classes: vec![],
+        };
+        let mut start = 0;
+        let mut kind = None;
+        for (i, c) in sel.char_indices().chain(std::iter::once((sel.len(), '.'))) {
+            if c != '#' && c != '.' {
+                continue;
+            }
+            let part = &sel[start..i];
+            match kind {
+                None if !part.is_empty() => selector.tag = Some(part),
+                None => {}
+                Some(_) if part.is_empty() => return None,
+                Some('#') => selector.id = Some(part),
+                Some(_) => selector.classes.push(part),
+            }
+            start = i + 1;
+            kind = Some(c);
+        }
+        let is_valid = |s: &str| {
+            s.chars()
+                .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+        };
+        let parts = selector
+            .tag
+            .iter()
+            .chain(&selector.id)
+            .chain(&selector.classes);
+        (start > 0 && parts.clone().all(|s| is_valid(s))).then_some(selector)
+    }
+
+    fn matches(&self, node: &SsrNode) -> bool {
+        let (name, attributes) = match node {
+            SsrNode::Container {
+                name, attributes, ..
+            } => (name, attributes),
+            SsrNode::Text(_) | SsrNode::Html(_) => return false,
+        };
+        let attrib = |key: &str| {
+            attributes
+                .iter()
+                .find_map(|(k, v)| (k == key).then(|| v.as_deref().unwrap_or("")))
+        };
+        self.tag.is_none_or(|tag| name.eq_ignore_ascii_case(tag))
+            && self.id.is_none_or(|id| attrib("id") == Some(id))
+            && self.classes.iter().all(|class| {
+                attrib("class").is_some_and(|cs| cs.split_whitespace().any(|c| c == *class))
+            })
+    }
 }
 
 /// A server side renderable DOM element.
@@ -176,14 +435,43 @@ pub struct SsrDom {
     /// The underlying node.
     pub node: Arc<RwLock<SsrNode>>,
     /// A map of events registered with this element.
-    pub events: Arc<
-        RwLock<
-            HashMap<
-                (&'static str, &'static str),
-                Pin<Box<dyn Sink<SsrDomEvent> + Send + Sync + 'static>>,
-            >,
-        >,
-    >,
+    pub events: Arc<SsrEvents>,
+    /// This view's parent, so that this view may find or remove itself.
+    pub(crate) parent: Arc<RwLock<WeakSsrDom>>,
+}
+
+type SsrEvents = RwLock<
+    HashMap<(&'static str, &'static str), Pin<Box<dyn Sink<SsrDomEvent> + Send + Sync + 'static>>>,
+>;
+
+/// A weak reference to an [`SsrDom`], linking a child to its parent without
+/// keeping the parent alive.
+#[derive(Clone, Default)]
+pub(crate) struct WeakSsrDom {
+    executor: Weak<Executor<'static>>,
+    node: Weak<RwLock<SsrNode>>,
+    events: Weak<SsrEvents>,
+    parent: Weak<RwLock<WeakSsrDom>>,
+}
+
+impl WeakSsrDom {
+    fn new(dom: &SsrDom) -> Self {
+        WeakSsrDom {
+            executor: Arc::downgrade(&dom.executor),
+            node: Arc::downgrade(&dom.node),
+            events: Arc::downgrade(&dom.events),
+            parent: Arc::downgrade(&dom.parent),
+        }
+    }
+
+    fn upgrade(&self) -> Option<SsrDom> {
+        Some(SsrDom {
+            executor: self.executor.upgrade()?,
+            node: self.node.upgrade()?,
+            events: self.events.upgrade()?,
+            parent: self.parent.upgrade()?,
+        })
+    }
 }
 
 impl Downcast<SsrDom> for AnyView {
@@ -212,16 +500,123 @@ impl TryFrom<ViewBuilder> for SsrDom {
 }
 
 impl SsrDom {
+    /// Build a server-side rendered view, spawning its tasks on the given
+    /// executor.
+    ///
+    /// Unlike [`SsrDom::try_from`], which creates a new executor for each view,
+    /// this allows many views (eg. all the views rendered for one request) to
+    /// share a single executor. Ticking that executor drives all of them.
     pub fn new(executor: Arc<Executor<'static>>, builder: ViewBuilder) -> anyhow::Result<Self> {
         build(&executor, builder)
     }
 
+    /// Replace all of this node's children with views built from the given
+    /// builders, in a single swap.
+    pub fn replace_children(&self, new: Vec<ViewBuilder>) -> anyhow::Result<()> {
+        let new = new
+            .into_iter()
+            .map(|builder| build(&self.executor, builder))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let mut lock = self.node.try_write().context("cannot write node")?;
+        if let SsrNode::Container { children, .. } = lock.deref_mut() {
+            orphan_children(&std::mem::replace(children, new))?;
+            adopt_children(self, children)
+        } else {
+            anyhow::bail!("not a container")
+        }
+    }
+
+    /// Replace this element's children with the given markup.
+    ///
+    /// ## Warning
+    /// The markup is not sanitized or escaped. It is written verbatim into
+    /// the output of [`SsrDom::html_string`], so only use this with trusted
+    /// markup.
+    ///
+    /// Fails if this is not a container element.
+    pub fn set_inner_html(&self, html: &str) -> anyhow::Result<()> {
+        let mut lock = self.node.try_write().context("cannot write node")?;
+        if let SsrNode::Container { children, .. } = lock.deref_mut() {
+            let new = if html.is_empty() {
+                vec![]
+            } else {
+                vec![SsrDom::html(self.executor.clone(), html)]
+            };
+            orphan_children(&std::mem::replace(children, new))?;
+            adopt_children(self, children)
+        } else {
+            anyhow::bail!("not a container")
+        }
+    }
+
+    /// Set a property of this element.
+    ///
+    /// Server-side there is no distinction between properties and attributes,
+    /// so strings and numbers are set as attributes, `true` sets a boolean
+    /// attribute and `false` or `null` removes the attribute.
+    ///
+    /// Fails if this is not a container element or the value is an array or
+    /// object.
+    pub fn set_property(&self, name: &str, value: Value) -> anyhow::Result<()> {
+        match value {
+            Value::String(s) => self.set_attrib(name, Some(&s)),
+            Value::Number(n) => self.set_attrib(name, Some(&n.to_string())),
+            Value::Bool(true) => self.set_attrib(name, None),
+            Value::Bool(false) | Value::Null => self.remove_attrib(name),
+            value => anyhow::bail!("unsupported property value: {}", value),
+        }
+    }
+
+    /// Remove this node from its parent's children.
+    ///
+    /// Does nothing if this node has no parent.
+    pub fn detach(&self) -> anyhow::Result<()> {
+        let mut parent = self.parent.try_write().context("cannot write parent")?;
+        if let Some(parent_node) = parent.node.upgrade() {
+            let mut lock = parent_node
+                .try_write()
+                .context("cannot write parent node")?;
+            if let SsrNode::Container { children, .. } = lock.deref_mut() {
+                children.retain(|child| !Arc::ptr_eq(&child.node, &self.node));
+            }
+        }
+        // only unlink once the parent no longer holds this node
+        *parent = WeakSsrDom::default();
+        Ok(())
+    }
+
+    /// Returns this node's parent, if it has one.
+    pub fn parent(&self) -> Option<SsrDom> {
+        self.parent.try_read()?.upgrade()
+    }
+
+    /// Returns this node's children in order, including text nodes.
+    ///
+    /// Text nodes have no children.
+    pub fn children(&self) -> Vec<SsrDom> {
+        match self.node.try_read().as_deref() {
+            Some(SsrNode::Container { children, .. }) => children.clone(),
+            _ => vec![],
+        }
+    }
+
     /// Creates a text node.
     pub fn text(executor: Arc<Executor<'static>>, s: &str) -> Self {
         SsrDom {
             executor,
             node: Arc::new(RwLock::new(SsrNode::Text(s.into()))),
             events: Default::default(),
+            parent: Default::default(),
+        }
+    }
+
+    /// Creates a node of raw markup, written verbatim.
+    fn html(executor: Arc<Executor<'static>>, html: &str) -> Self {
+        SsrDom {
+            executor,
+            node: Arc::new(RwLock::new(SsrNode::Html(html.into()))),
+            events: Default::default(),
+            parent: Default::default(),
         }
     }
 
@@ -236,22 +631,41 @@ impl SsrDom {
                 children: vec![],
             })),
             events: Default::default(),
+            parent: Default::default(),
         }
     }
 
     /// Set the text.
     ///
+    /// The node's existing buffer is reused, so setting the same (or shorter)
+    /// text does not allocate.
+    ///
     /// Fails if this element is not a text node.
     pub fn set_text(&self, text: &str) -> anyhow::Result<()> {
         let mut lock = self.node.try_write().context("can't lock for writing")?;
         if let SsrNode::Text(prev) = lock.deref_mut() {
-            *prev = text.to_string();
+            if prev != text {
+                prev.clear();
+                prev.push_str(text);
+            }
         } else {
             anyhow::bail!("not a text node");
         }
         Ok(())
     }
 
+    /// Get the text currently displayed by this text node.
+    ///
+    /// Fails if this is not a text node.
+    pub fn get_text(&self) -> anyhow::Result<String> {
+        let lock = self.node.try_read().context("can't lock for reading")?;
+        if let SsrNode::Text(text) = lock.deref() {
+            Ok(text.clone())
+        } else {
+            anyhow::bail!("not a text node")
+        }
+    }
+
     /// Add an attribute.
     ///
     /// Fails if this element is not a container.
@@ -302,8 +716,14 @@ impl SsrDom {
 
     /// Add a style property.
     ///
+    /// Like `CSSStyleDeclaration.setProperty` in the browser, setting an
+    /// empty value removes the property.
+    ///
     /// Fails if this is not a container element.
     pub fn set_style(&self, key: &str, value: &str) -> anyhow::Result<()> {
+        if value.is_empty() {
+            return self.remove_style(key);
+        }
         let mut lock = self.node.try_write().context("can't lock for writing")?;
         if let SsrNode::Container { styles, .. } = lock.deref_mut() {
             for (pkey, pval) in styles.iter_mut() {
@@ -320,6 +740,20 @@ impl SsrDom {
         Ok(())
     }
 
+    /// Get a style property, if it is set.
+    ///
+    /// Fails if this is not a container element.
+    pub fn get_style(&self, key: &str) -> anyhow::Result<Option<String>> {
+        let lock = self.node.try_read().context("can't lock for reading")?;
+        if let SsrNode::Container { styles, .. } = lock.deref() {
+            Ok(styles
+                .iter()
+                .find_map(|(pkey, pval)| (pkey == key).then(|| pval.clone())))
+        } else {
+            anyhow::bail!("not a container")
+        }
+    }
+
     /// Remove a style property.
     ///
     /// Fails if this not a container element.
@@ -333,6 +767,56 @@ impl SsrDom {
         Ok(())
     }
 
+    /// Returns the first descendant of this element that matches the CSS
+    /// selector `sel`, in document order.
+    ///
+    /// Only compound selectors made of an optional tag name, an optional
+    /// `#id` and any number of `.class`es are supported, eg.
+    /// `button#save.primary`. Returns `None` if nothing matches or the
+    /// selector is not supported.
+    pub fn query_selector(&self, sel: &str) -> Option<SsrDom> {
+        let selector = SimpleSelector::parse(sel)?;
+        self.find(&selector, false)
+    }
+
+    fn find(&self, selector: &SimpleSelector<'_>, include_self: bool) -> Option<SsrDom> {
+        if include_self && selector.matches(&*self.node.try_read()?) {
+            return Some(self.clone());
+        }
+        self.children()
+            .iter()
+            .find_map(|child| child.find(selector, true))
+    }
+
+    /// Synchronously send an event to the listener of this element, or of the
+    /// first descendant, that matches the CSS selector `sel`.
+    ///
+    /// The event is delivered as if it were fired on the element itself,
+    /// like [`SsrDom::fire_event`] with `"myself"`. This makes it possible to
+    /// drive a view's event logic in tests without a browser. See
+    /// [`SsrDom::query_selector`] for the supported selectors.
+    ///
+    /// Fails if no element matches, the element has no listener for `name`,
+    /// or the listener's sink cannot accept the event right now.
+    pub fn dispatch_event(
+        &self,
+        sel: &str,
+        name: &'static str,
+        event: SsrDomEvent,
+    ) -> anyhow::Result<()> {
+        let selector = SimpleSelector::parse(sel)
+            .with_context(|| format!("unsupported selector '{}'", sel))?;
+        let target = self
+            .find(&selector, true)
+            .with_context(|| format!("no element matches '{}'", sel))?;
+        let events = target.events.try_read().context("can't lock events")?;
+        let sink = events
+            .get(&("myself", name))
+            .with_context(|| format!("'{}' has no '{}' listener", sel, name))?;
+        sink.try_send(event)
+            .map_err(|e| anyhow::anyhow!("could not dispatch '{}' to '{}': {}", name, sel, e))
+    }
+
     /// Fires an event downstream to any listening
     /// [`Stream`](crate::core::stream::Stream)s.
     ///
@@ -352,6 +836,157 @@ impl SsrDom {
         sink.send(event).await.map_err(Either::Right)
     }
 
+    /// Structured representation of the DOM tree.
+    ///
+    /// Text nodes are JSON strings and raw markup is an object with an
+    /// `html` string. Elements are objects with a `tag` name, an `attrs`
+    /// object (boolean attributes have `null` values and styles are merged
+    /// into `style`) and a `children` array.
+    pub fn to_json(&self) -> Pin<Box<dyn Future<Output = Value> + Send>> {
+        let node = self.node.clone();
+        Box::pin(async move {
+            let lock = node.read().await;
+            match lock.deref() {
+                SsrNode::Text(s) => Value::String(s.clone()),
+                SsrNode::Html(s) => serde_json::json!({ "html": s }),
+                SsrNode::Container {
+                    name,
+                    attributes,
+                    styles,
+                    children,
+                } => {
+                    let attrs = SsrNode::attributes_with_styles(attributes, styles)
+                        .into_iter()
+                        .map(|(k, v)| (k, v.map(Value::String).unwrap_or(Value::Null)))
+                        .collect::<serde_json::Map<_, _>>();
+                    let mut kids = vec![];
+                    for kid in children.iter() {
+                        kids.push(kid.to_json().await);
+                    }
+                    serde_json::json!({
+                        "tag": name,
+                        "attrs": attrs,
+                        "children": kids,
+                    })
+                }
+            }
+        })
+    }
+
+    /// Compare this tree with another, returning their structural, attribute
+    /// and text differences.
+    ///
+    /// This is more actionable than comparing [`SsrDom::html_string`]s, as
+    /// each difference is reported separately along with its location.
+    pub async fn diff(&self, other: &SsrDom) -> Vec<ViewDiff> {
+        let mut diffs = vec![];
+        self.diff_at(other, vec![], &mut diffs).await;
+        diffs
+    }
+
+    fn diff_at<'a>(
+        &'a self,
+        other: &'a SsrDom,
+        path: Vec<usize>,
+        diffs: &'a mut Vec<ViewDiff>,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        Box::pin(async move {
+            if Arc::ptr_eq(&self.node, &other.node) {
+                return;
+            }
+            let left = self.node.read().await;
+            let right = other.node.read().await;
+            match (left.deref(), right.deref()) {
+                (SsrNode::Text(left), SsrNode::Text(right))
+                | (SsrNode::Html(left), SsrNode::Html(right)) => {
+                    if left != right {
+                        diffs.push(ViewDiff::Text {
+                            path,
+                            left: left.clone(),
+                            right: right.clone(),
+                        });
+                    }
+                }
+                (
+                    SsrNode::Container {
+                        name: left_name,
+                        attributes: left_attributes,
+                        styles: left_styles,
+                        children: left_children,
+                    },
+                    SsrNode::Container {
+                        name: right_name,
+                        attributes: right_attributes,
+                        styles: right_styles,
+                        children: right_children,
+                    },
+                ) if left_name == right_name => {
+                    let attributes = |attributes, styles| {
+                        SsrNode::attributes_with_styles(attributes, styles)
+                            .into_iter()
+                            .map(|(k, v)| (k, v.unwrap_or_default()))
+                            .collect::<Vec<_>>()
+                    };
+                    let left_attributes = attributes(left_attributes, left_styles);
+                    let right_attributes = attributes(right_attributes, right_styles);
+                    let find = |attributes: &[(String, String)], name: &str| {
+                        attributes
+                            .iter()
+                            .find(|(k, _)| k == name)
+                            .map(|(_, v)| v.clone())
+                    };
+                    let names = left_attributes
+                        .iter()
+                        .chain(right_attributes.iter())
+                        .map(|(k, _)| k.clone())
+                        .collect::<Vec<_>>();
+                    let mut seen = vec![];
+                    for name in names {
+                        if seen.contains(&name) {
+                            continue;
+                        }
+                        let left = find(&left_attributes, &name);
+                        let right = find(&right_attributes, &name);
+                        if left != right {
+                            diffs.push(ViewDiff::Attribute {
+                                path: path.clone(),
+                                name: name.clone(),
+                                left,
+                                right,
+                            });
+                        }
+                        seen.push(name);
+                    }
+
+                    if left_children.len() != right_children.len() {
+                        diffs.push(ViewDiff::Children {
+                            path: path.clone(),
+                            left: left_children.len(),
+                            right: right_children.len(),
+                        });
+                    }
+                    for (i, (left, right)) in left_children.iter().zip(right_children).enumerate() {
+                        let mut path = path.clone();
+                        path.push(i);
+                        left.diff_at(right, path, diffs).await;
+                    }
+                }
+                (left, right) => {
+                    let describe = |node: &SsrNode| match node {
+                        SsrNode::Text(_) => "#text".to_string(),
+                        SsrNode::Html(_) => "#html".to_string(),
+                        SsrNode::Container { name, .. } => name.to_string(),
+                    };
+                    diffs.push(ViewDiff::Node {
+                        path,
+                        left: describe(left),
+                        right: describe(right),
+                    });
+                }
+            }
+        })
+    }
+
     /// Removes an event.
     pub fn remove_event(&self, type_is: &'static str, name: &'static str) {
         let mut lock = self.events.try_write().unwrap();
@@ -367,6 +1002,53 @@ impl SsrDom {
         })
     }
 
+    /// String value with each element on its own line, for snapshot tests
+    /// and debugging.
+    ///
+    /// Nested elements are indented by `indent` spaces per level and elements
+    /// that only contain text are kept on one line. Use
+    /// [`SsrDom::html_string`] for compact output.
+    pub fn html_string_pretty(
+        &self,
+        indent: usize,
+    ) -> Pin<Box<dyn Future<Output = String> + Send>> {
+        self.html_string_pretty_at(indent, 0)
+    }
+
+    fn html_string_pretty_at(
+        &self,
+        indent: usize,
+        depth: usize,
+    ) -> Pin<Box<dyn Future<Output = String> + Send>> {
+        let node = self.node.clone();
+        Box::pin(async move {
+            let lock = node.read().await;
+            lock.html_string_pretty(indent, depth).await
+        })
+    }
+
+    /// Write this view's HTML into `w`, without building the whole document
+    /// as one `String`.
+    ///
+    /// Pending updates are applied first by ticking the executor until it is
+    /// idle, then the tree is written synchronously. The output is the same as
+    /// [`SsrDom::html_string`].
+    pub async fn render_into(&self, w: &mut impl std::fmt::Write) -> anyhow::Result<()> {
+        self.wait_for_steady_state().await;
+        let lock = self.node.read().await;
+        lock.render_into(w)
+    }
+
+    /// Tick this view's executor until it has no more work to do.
+    ///
+    /// When this returns every update that was already sent to the view's
+    /// attribute, style, text and child streams has been applied, so the
+    /// view can be inspected without polling for the expected output.
+    /// Updates that arrive later (eg. after a timer) are not waited on.
+    pub async fn wait_for_steady_state(&self) {
+        while self.executor.try_tick() {}
+    }
+
     pub async fn run_while<T: 'static>(
         &self,
         fut: impl Future<Output = T> + 'static,
@@ -375,6 +1057,18 @@ impl SsrDom {
         Ok(t)
     }
 
+    /// Tick this view's executor until `fut` completes, or until `millis`
+    /// milliseconds pass.
+    ///
+    /// Returns an error if the deadline passes first.
+    pub async fn run_while_timeout<T: 'static>(
+        &self,
+        millis: u64,
+        fut: impl Future<Output = T> + 'static,
+    ) -> anyhow::Result<T> {
+        self.run_while(super::timeout(millis, fut)).await?
+    }
+
     pub fn update(&self, update: Update) -> anyhow::Result<()> {
         match update {
             Update::Text(s) => {
@@ -383,6 +1077,10 @@ impl SsrDom {
             Update::Attribute(patch) => match patch {
                 HashPatch::Insert(k, v) => self.set_attrib(&k, Some(&v))?,
                 HashPatch::Remove(k) => self.remove_attrib(&k)?,
+                patch @ HashPatch::Modify { .. } => {
+                    let patch = patch.resolve(|k| self.get_attrib(k).ok().flatten());
+                    self.update(Update::Attribute(patch))?
+                }
             },
             Update::BooleanAttribute(patch) => match patch {
                 HashPatch::Insert(k, v) => {
@@ -393,19 +1091,29 @@ impl SsrDom {
                     }
                 }
                 HashPatch::Remove(k) => self.remove_attrib(&k)?,
+                patch @ HashPatch::Modify { .. } => {
+                    let patch = patch.resolve(|k| self.get_attrib(k).ok().map(|_| true));
+                    self.update(Update::BooleanAttribute(patch))?
+                }
             },
             Update::Style(patch) => match patch {
                 HashPatch::Insert(k, v) => self.set_style(&k, &v)?,
                 HashPatch::Remove(k) => self.remove_style(&k)?,
+                patch @ HashPatch::Modify { .. } => {
+                    let patch = patch.resolve(|k| self.get_style(k).ok().flatten());
+                    self.update(Update::Style(patch))?
+                }
             },
             Update::Child(patch) => {
                 let patch = patch.try_map(|builder: ViewBuilder| {
+                    let builder = super::ensure_not_fragment(builder)?;
                     let ssr = SsrDom::new(self.executor.clone(), builder)?;
                     anyhow::Ok(ssr)
                 })?;
                 let mut lock = self.node.try_write().context("can't lock")?;
                 if let SsrNode::Container { children, .. } = lock.deref_mut() {
-                    let _ = children.list_patch_apply(patch);
+                    orphan_children(&children.list_patch_apply(patch))?;
+                    adopt_children(self, children)?;
                 } else {
                     anyhow::bail!("not a container")
                 }
@@ -428,6 +1136,22 @@ impl SsrDom {
     }
 }
 
+/// Point each child at its parent, so it can find or detach itself later.
+fn adopt_children(parent: &SsrDom, children: &[SsrDom]) -> anyhow::Result<()> {
+    for child in children.iter() {
+        *child.parent.try_write().context("cannot write parent")? = WeakSsrDom::new(parent);
+    }
+    Ok(())
+}
+
+/// Unlink removed children from their old parent.
+fn orphan_children(removed: &[SsrDom]) -> anyhow::Result<()> {
+    for child in removed.iter() {
+        *child.parent.try_write().context("cannot write parent")? = WeakSsrDom::default();
+    }
+    Ok(())
+}
+
 pub(crate) fn build(
     executor: &Arc<Executor<'static>>,
     builder: ViewBuilder,
@@ -451,6 +1175,8 @@ pub(crate) fn build(
             el
         }
         ViewIdentity::Leaf(text) => SsrDom::text(executor.clone(), &text),
+        // a container without a name renders as just its children
+        ViewIdentity::Fragment => SsrDom::element(executor.clone(), ""),
     };
 
     for update in initial_values.into_iter() {
@@ -504,7 +1230,10 @@ impl ListPatchApply for SsrDom {
     fn list_patch_apply(&mut self, patch: ListPatch<Self::Item>) -> Vec<Self::Item> {
         let mut lock = self.node.try_write().unwrap();
         if let SsrNode::Container { children, .. } = lock.deref_mut() {
-            children.list_patch_apply(patch)
+            let removed = children.list_patch_apply(patch);
+            orphan_children(&removed).unwrap();
+            adopt_children(self, children).unwrap();
+            removed
         } else {
             panic!("not a container")
         }
@@ -533,4 +1262,742 @@ mod ssr {
         assert!((any_view.downcast_mut() as Option<&mut SsrDom>).is_some());
         let _ssr: SsrDom = any_view.downcast().unwrap();
     }
+
+    #[test]
+    fn ssr_views_can_share_an_executor() {
+        use std::sync::Arc;
+
+        use async_executor::Executor;
+
+        let executor = Arc::new(Executor::default());
+        let mut first_text = Input::<String>::default();
+        let mut second_text = Input::<String>::default();
+        let first = SsrDom::new(
+            executor.clone(),
+            rsx! { p() {{("one", first_text.stream().unwrap())}} },
+        )
+        .unwrap();
+        let second = SsrDom::new(
+            executor.clone(),
+            rsx! { p() {{("two", second_text.stream().unwrap())}} },
+        )
+        .unwrap();
+        assert!(Arc::ptr_eq(&first.executor, &second.executor));
+
+        futures_lite::future::block_on(executor.run(async {
+            first_text.set("uno").await.unwrap();
+            second_text.set("dos").await.unwrap();
+            let rendered = mogwai_dom::core::time::wait_for_async(1.0, || async {
+                let first = first.html_string().await;
+                let second = second.html_string().await;
+                (first == "<p>uno</p>" && second == "<p>dos</p>").then_some(())
+            })
+            .await;
+            assert!(rendered.is_ok(), "views were not both updated");
+        }));
+    }
+
+    #[test]
+    fn ssr_task_on_first_runs_once() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let runs = std::sync::Arc::new(AtomicUsize::new(0));
+        let task_runs = runs.clone();
+        let dom = SsrDom::try_from(ViewBuilder::element("div").with_task_on_first(
+            "click",
+            move |_: SsrDom| async move {
+                task_runs.fetch_add(1, Ordering::SeqCst);
+            },
+        ))
+        .unwrap();
+        futures_lite::future::block_on(dom.executor.run(async {
+            for _ in 0..3 {
+                let _ = dom
+                    .fire_event("myself", "click", super::SsrDomEvent(serde_json::Value::Null))
+                    .await;
+                mogwai::time::wait_millis(2).await;
+            }
+        }));
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn ssr_typed_input_value() {
+        let (tx, mut rx) = mogwai::channel::mpsc::bounded::<String>(1);
+        let dom = SsrDom::try_from(rsx! {
+            input(on:input(value) = tx) {}
+        })
+        .unwrap();
+        futures_lite::future::block_on(dom.executor.run(async {
+            let input = serde_json::json!({"target": {"value": "hello"}});
+            dom.fire_event("myself", "input", super::SsrDomEvent(input))
+                .await
+                .unwrap();
+            assert_eq!(rx.next().await.as_deref(), Some("hello"));
+        }));
+    }
+
+    #[test]
+    fn ssr_bind_value_round_trips() {
+        let name = mogwai::relay::FanInput::<String>::default();
+        let mut seen = name.stream();
+        let dom = SsrDom::try_from(rsx! {
+            input(bind:value = name) {}
+        })
+        .unwrap();
+        futures_lite::future::block_on(dom.executor.run(async {
+            let input = serde_json::json!({"target": {"value": "h"}});
+            dom.fire_event("myself", "input", super::SsrDomEvent(input))
+                .await
+                .unwrap();
+            assert_eq!(seen.next().await.as_deref(), Some("h"));
+            mogwai::time::wait_millis(1).await;
+            assert_eq!(dom.html_string().await, r#"<input value="h" />"#);
+
+            name.set("hi").await.unwrap();
+            assert_eq!(seen.next().await.as_deref(), Some("hi"));
+            mogwai::time::wait_millis(1).await;
+            assert_eq!(dom.html_string().await, r#"<input value="hi" />"#);
+        }));
+    }
+
+    #[test]
+    fn ssr_bind_checked_round_trips() {
+        let done = mogwai::relay::FanInput::<bool>::default();
+        let mut seen = done.stream();
+        let dom = SsrDom::try_from(rsx! {
+            input(type = "checkbox", bind:checked = done) {}
+        })
+        .unwrap();
+        futures_lite::future::block_on(dom.executor.run(async {
+            let change = serde_json::json!({"target": {"checked": true}});
+            dom.fire_event("myself", "change", super::SsrDomEvent(change))
+                .await
+                .unwrap();
+            assert_eq!(seen.next().await, Some(true));
+            mogwai::time::wait_millis(1).await;
+            assert_eq!(
+                dom.html_string().await,
+                r#"<input type="checkbox" checked />"#
+            );
+        }));
+    }
+
+    #[test]
+    fn ssr_optional_and_collected_children() {
+        #[derive(Clone)]
+        struct Badge(&'static str);
+
+        impl From<Badge> for ViewBuilder {
+            fn from(Badge(label): Badge) -> Self {
+                rsx! { span(class = "badge") {{label}} }
+            }
+        }
+
+        let none: Option<Badge> = None;
+        let some = Some(Badge("new"));
+        let badges = vec![Badge("a"), Badge("b")];
+        let ssr = SsrDom::try_from(rsx! {
+            div() {
+                {none}
+                {some}
+                {&badges[..]}
+                {badges}
+            }
+        })
+        .unwrap();
+        let html = futures_lite::future::block_on(ssr.html_string());
+        assert_eq!(
+            html,
+            r#"<div><span class="badge">new</span> <span class="badge">a</span> <span class="badge">b</span> <span class="badge">a</span> <span class="badge">b</span></div>"#
+        );
+    }
+
+    #[test]
+    fn ssr_child_if() {
+        let view = |logged_in: bool| {
+            let ssr = SsrDom::try_from(
+                ViewBuilder::element("nav")
+                    .append(rsx! { a(href = "/") {"home"} })
+                    .with_child_if(logged_in, rsx! { a(href = "/logout") {"logout"} }),
+            )
+            .unwrap();
+            futures_lite::future::block_on(ssr.html_string())
+        };
+        assert_eq!(
+            view(true),
+            r#"<nav><a href="/">home</a> <a href="/logout">logout</a></nav>"#
+        );
+        assert_eq!(view(false), r#"<nav><a href="/">home</a></nav>"#);
+    }
+
+    #[test]
+    fn ssr_query_selector() {
+        let ssr = SsrDom::try_from(rsx! {
+            div(id = "main") {
+                p(class = "note") {"one"}
+                button(id = "save", class = "btn primary") {"save"}
+            }
+        })
+        .unwrap();
+        let html = |dom: Option<SsrDom>| futures_lite::future::block_on(dom.unwrap().html_string());
+        assert_eq!(
+            html(ssr.query_selector("p.note")),
+            r#"<p class="note">one</p>"#
+        );
+        let save = r#"<button id="save" class="btn primary">save</button>"#;
+        assert_eq!(html(ssr.query_selector("#save")), save);
+        assert_eq!(html(ssr.query_selector("button.primary.btn")), save);
+        assert!(ssr.query_selector("#main").is_none());
+        assert!(ssr.query_selector("button.secondary").is_none());
+        assert!(ssr.query_selector("div > p").is_none());
+    }
+
+    #[test]
+    fn ssr_to_json() {
+        let ssr = SsrDom::try_from(rsx! {
+            div(id = "main", style:color = "red") {
+                p() {"Hello"}
+                input(boolean:checked = true) {}
+            }
+        })
+        .unwrap();
+        futures_lite::future::block_on(async {
+            assert_eq!(
+                ssr.to_json().await,
+                serde_json::json!({
+                    "tag": "div",
+                    "attrs": {"id": "main", "style": "color: red;"},
+                    "children": [
+                        {"tag": "p", "attrs": {}, "children": ["Hello"]},
+                        {"tag": "input", "attrs": {"checked": null}, "children": []}
+                    ]
+                })
+            );
+        });
+    }
+
+    #[test]
+    fn ssr_render_into() {
+        let (tx, rx) = mogwai::channel::mpsc::bounded::<String>(1);
+        let ssr = SsrDom::try_from(rsx! {
+            div(id = "main", style:color = "red") {
+                p(class = rx) {"Hello"}
+                input(boolean:checked = true, style:width = "2em") {}
+                br() {}
+                ul() {
+                    li() {"one"}
+                    li() {"two"}
+                }
+            }
+        })
+        .unwrap();
+        futures_lite::future::block_on(async {
+            tx.send("greeting".into()).await.unwrap();
+            let mut rendered = String::new();
+            ssr.render_into(&mut rendered).await.unwrap();
+            assert!(rendered.contains(r#"<p class="greeting">"#), "{}", rendered);
+            assert_eq!(rendered, ssr.html_string().await);
+        });
+    }
+
+    #[test]
+    fn ssr_input_stream_with_initial() {
+        let mut input = Input::<String>::default();
+        input.try_set("hello").unwrap();
+        let ssr = SsrDom::try_from(ViewBuilder::element("p").append(ViewBuilder::text(
+            input.stream_with_initial().unwrap(),
+        )))
+        .unwrap();
+        assert!(input.stream_with_initial().is_none());
+        futures_lite::future::block_on(async {
+            let mut rendered = String::new();
+            ssr.render_into(&mut rendered).await.unwrap();
+            assert_eq!(rendered, "<p>hello</p>");
+
+            input.set("goodbye").await.unwrap();
+            let mut rendered = String::new();
+            ssr.render_into(&mut rendered).await.unwrap();
+            assert_eq!(rendered, "<p>goodbye</p>");
+        });
+    }
+
+    #[test]
+    fn ssr_get_text() {
+        let text = SsrDom::try_from(ViewBuilder::text("clicked 0 times")).unwrap();
+        assert_eq!(text.get_text().unwrap(), "clicked 0 times");
+        text.set_text("clicked 1 time").unwrap();
+        assert_eq!(text.get_text().unwrap(), "clicked 1 time");
+
+        let el = SsrDom::try_from(ViewBuilder::element("p")).unwrap();
+        assert!(el.get_text().is_err());
+    }
+
+    #[test]
+    fn ssr_diff() {
+        let left = SsrDom::try_from(rsx! {
+            div(id = "main") {
+                p(class = "greeting") {"Hello"}
+                input(boolean:checked = true) {}
+            }
+        })
+        .unwrap();
+        let right = SsrDom::try_from(rsx! {
+            div(id = "main") {
+                p(class = "farewell") {"Hello"}
+                input(boolean:checked = true) {}
+            }
+        })
+        .unwrap();
+        futures_lite::future::block_on(async {
+            assert!(left.diff(&left.clone()).await.is_empty());
+            assert_eq!(
+                left.diff(&right).await,
+                vec![ViewDiff::Attribute {
+                    path: vec![0],
+                    name: "class".to_string(),
+                    left: Some("greeting".to_string()),
+                    right: Some("farewell".to_string()),
+                }]
+            );
+
+            let other = SsrDom::try_from(rsx! {
+                div(style:color = "red") {
+                    "Hello"
+                }
+            })
+            .unwrap();
+            assert_eq!(
+                left.diff(&other).await,
+                vec![
+                    ViewDiff::Attribute {
+                        path: vec![],
+                        name: "id".to_string(),
+                        left: Some("main".to_string()),
+                        right: None,
+                    },
+                    ViewDiff::Attribute {
+                        path: vec![],
+                        name: "style".to_string(),
+                        left: None,
+                        right: Some("color: red;".to_string()),
+                    },
+                    ViewDiff::Children {
+                        path: vec![],
+                        left: 2,
+                        right: 1,
+                    },
+                    ViewDiff::Node {
+                        path: vec![0],
+                        left: "p".to_string(),
+                        right: "#text".to_string(),
+                    },
+                ]
+            );
+        });
+    }
+
+    #[test]
+    fn ssr_move_child() {
+        use std::ops::Deref;
+
+        let (tx, rx) = mogwai::channel::mpsc::bounded(1);
+        let ssr = SsrDom::try_from(rsx! {
+            ol(patch:children = rx) {
+                li() {"one"}
+                li() {"two"}
+                li() {"three"}
+            }
+        })
+        .unwrap();
+        let children = || match ssr.node.try_read().unwrap().deref() {
+            super::SsrNode::Container { children, .. } => children.clone(),
+            _ => unreachable!(),
+        };
+        let first = children().remove(0);
+        futures_lite::future::block_on(ssr.executor.run(async {
+            tx.send(ListPatch::move_item(0, 2)).await.unwrap();
+            let moved = mogwai::time::repeat_times(0.1, 10, || async {
+                ssr.html_string().await == "<ol><li>two</li> <li>three</li> <li>one</li></ol>"
+            })
+            .await;
+            assert!(moved.is_ok(), "{}", ssr.html_string().await);
+        }));
+        // the moved child is the same node, not a rebuilt one
+        assert!(std::sync::Arc::ptr_eq(&children()[2].node, &first.node));
+    }
+
+    #[test]
+    fn ssr_replace_child() {
+        use std::ops::Deref;
+
+        let (tx, rx) = mogwai::channel::mpsc::bounded(1);
+        let ssr = SsrDom::try_from(rsx! {
+            ol(patch:children = rx) {
+                li() {"one"}
+                li() {"two"}
+                li() {"three"}
+            }
+        })
+        .unwrap();
+        let children = || match ssr.node.try_read().unwrap().deref() {
+            super::SsrNode::Container { children, .. } => children.clone(),
+            _ => unreachable!(),
+        };
+        let before = children();
+        futures_lite::future::block_on(ssr.executor.run(async {
+            tx.send(ListPatch::replace(1, rsx! { li() {"deux"} }))
+                .await
+                .unwrap();
+            let replaced = mogwai::time::repeat_times(0.1, 10, || async {
+                ssr.html_string().await == "<ol><li>one</li> <li>deux</li> <li>three</li></ol>"
+            })
+            .await;
+            assert!(replaced.is_ok(), "{}", ssr.html_string().await);
+        }));
+        // only the target node changed
+        let after = children();
+        assert!(std::sync::Arc::ptr_eq(&after[0].node, &before[0].node));
+        assert!(!std::sync::Arc::ptr_eq(&after[1].node, &before[1].node));
+        assert!(std::sync::Arc::ptr_eq(&after[2].node, &before[2].node));
+    }
+
+    #[test]
+    fn ssr_batch_children() {
+        let (tx, rx) = mogwai::channel::mpsc::bounded(1);
+        let ssr = SsrDom::try_from(rsx! {
+            ol(patch:children = rx) {
+                li() {"one"}
+            }
+        })
+        .unwrap();
+        futures_lite::future::block_on(ssr.executor.run(async {
+            tx.send(ListPatch::batch(vec![
+                ListPatch::push(rsx! { li() {"two"} }),
+                ListPatch::insert(0, rsx! { li() {"zero"} }),
+                ListPatch::move_item(2, 1),
+                ListPatch::push(rsx! { li() {"three"} }),
+            ]))
+            .await
+            .unwrap();
+            let patched = mogwai::time::repeat_times(0.1, 10, || async {
+                ssr.html_string().await
+                    == "<ol><li>zero</li> <li>two</li> <li>one</li> <li>three</li></ol>"
+            })
+            .await;
+            assert!(patched.is_ok(), "{}", ssr.html_string().await);
+        }));
+    }
+
+    #[test]
+    fn ssr_detach() {
+        futures_lite::future::block_on(async {
+            let mut parent = SsrDom::try_from(rsx! { ul() { li() {"one"} } }).unwrap();
+            let child = SsrDom::new(parent.executor.clone(), rsx! { li() {"two"} }).unwrap();
+            let _ = parent.list_patch_apply(ListPatch::push(child.clone()));
+            assert_eq!(
+                parent.html_string().await,
+                "<ul><li>one</li> <li>two</li></ul>"
+            );
+
+            // a failed detach keeps the node linked to its parent
+            {
+                let _lock = parent.node.try_write().unwrap();
+                assert!(child.detach().is_err());
+            }
+            assert!(child.parent().is_some());
+
+            child.detach().unwrap();
+            assert_eq!(parent.html_string().await, "<ul><li>one</li></ul>");
+            assert!(child.parent().is_none());
+
+            // detaching a node without a parent is a no-op
+            child.detach().unwrap();
+            parent.detach().unwrap();
+            assert_eq!(parent.html_string().await, "<ul><li>one</li></ul>");
+        });
+    }
+
+    #[test]
+    fn ssr_removed_children_have_no_parent() {
+        let mut parent = SsrDom::try_from(rsx! { ul() { li() {"one"} li() {"two"} } }).unwrap();
+        let children = parent.children();
+        assert!(children.iter().all(|child| child.parent().is_some()));
+
+        parent.update(Update::Child(ListPatch::remove(0))).unwrap();
+        assert!(children[0].parent().is_none());
+
+        let removed = parent.list_patch_apply(ListPatch::remove(0));
+        assert!(removed[0].parent().is_none());
+        assert!(children[1].parent().is_none());
+
+        parent.replace_children(vec![rsx! { li() {"three"} }]).unwrap();
+        let three = parent.children().remove(0);
+        assert!(three.parent().is_some());
+        parent.replace_children(vec![]).unwrap();
+        assert!(three.parent().is_none());
+
+        parent.replace_children(vec![rsx! { li() {"four"} }]).unwrap();
+        let four = parent.children().remove(0);
+        parent.set_inner_html("<li>five</li>").unwrap();
+        assert!(four.parent().is_none());
+    }
+
+    #[test]
+    fn ssr_set_inner_html() {
+        futures_lite::future::block_on(async {
+            let view = SsrDom::try_from(rsx! { div() { p() {"replaced"} } }).unwrap();
+            view.set_inner_html("<b>hi</b>").unwrap();
+            assert_eq!(view.html_string().await, "<div><b>hi</b></div>");
+            // the markup is kept as one raw node, not as text
+            let children = view.children();
+            assert_eq!(children.len(), 1);
+            assert!(matches!(
+                &*children[0].node.try_read().unwrap(),
+                super::SsrNode::Html(html) if html == "<b>hi</b>"
+            ));
+            assert!(children[0].get_text().is_err());
+            assert_eq!(
+                view.to_json().await,
+                serde_json::json!({
+                    "tag": "div",
+                    "attrs": {},
+                    "children": [{ "html": "<b>hi</b>" }],
+                })
+            );
+            assert!(view.query_selector("b").is_none());
+
+            view.set_inner_html("").unwrap();
+            assert_eq!(view.html_string().await, "<div></div>");
+
+            let text = SsrDom::try_from(ViewBuilder::text("hi")).unwrap();
+            assert!(text.set_inner_html("<b>hi</b>").is_err());
+        });
+    }
+
+    #[test]
+    fn ssr_fragment() {
+        let fragment = ViewBuilder::fragment(vec![rsx! { p() {"one"} }, rsx! { p() {"two"} }]);
+        assert!(fragment.is_fragment());
+        let ssr = SsrDom::try_from(fragment).unwrap();
+        futures_lite::future::block_on(async {
+            assert_eq!(ssr.html_string().await, "<p>one</p> <p>two</p>");
+            assert_eq!(ssr.html_string_pretty(2).await, "<p>one</p>\n<p>two</p>");
+            let mut rendered = String::new();
+            ssr.render_into(&mut rendered).await.unwrap();
+            assert_eq!(rendered, "<p>one</p> <p>two</p>");
+        });
+    }
+
+    #[test]
+    fn ssr_append_fragment() {
+        let items = ViewBuilder::fragment(vec![
+            rsx! { li() {"two"} },
+            ViewBuilder::fragment(vec![rsx! { li() {"three"} }]),
+        ]);
+        let builder = rsx! {
+            ul() {
+                li() {"one"}
+                {items}
+                li() {"four"}
+            }
+        };
+        let ssr = SsrDom::try_from(builder).unwrap();
+        assert_eq!(ssr.children().len(), 4);
+        assert_eq!(
+            futures_lite::future::block_on(ssr.html_string()),
+            "<ul><li>one</li> <li>two</li> <li>three</li> <li>four</li></ul>"
+        );
+    }
+
+    #[test]
+    fn ssr_fragment_cannot_be_patched_in() {
+        let ssr = SsrDom::try_from(rsx! { ul() { li() {"one"} } }).unwrap();
+        let fragment = ViewBuilder::fragment(vec![rsx! { li() {"two"} }, rsx! { li() {"three"} }]);
+        assert!(ssr
+            .update(Update::Child(ListPatch::push(fragment)))
+            .is_err());
+        assert_eq!(ssr.children().len(), 1);
+
+        // later patches still target the right children
+        ssr.update(Update::Child(ListPatch::push(rsx! { li() {"two"} })))
+            .unwrap();
+        ssr.update(Update::Child(ListPatch::remove(0))).unwrap();
+        assert_eq!(
+            futures_lite::future::block_on(ssr.html_string()),
+            "<ul><li>two</li></ul>"
+        );
+    }
+
+    #[test]
+    fn ssr_html_string_pretty() {
+        let ssr = SsrDom::try_from(rsx! {
+            div(id = "main") {
+                p() {"Hello"}
+                p(class = "nested") {
+                    "Hi"
+                    span() {"there"}
+                }
+                br() {}
+            }
+        })
+        .unwrap();
+        let expected = r#"<div id="main">
+  <p>Hello</p>
+  <p class="nested">
+    Hi
+    <span>there</span>
+  </p>
+  <br />
+</div>"#;
+        futures_lite::future::block_on(async {
+            assert_eq!(ssr.html_string_pretty(2).await, expected);
+            // the compact output is unchanged
+            assert_eq!(
+                ssr.html_string().await,
+                r#"<div id="main"><p>Hello</p> <p class="nested">Hi <span>there</span></p> <br /></div>"#
+            );
+        });
+    }
+
+    #[test]
+    fn ssr_post_build_async() {
+        let id = mogwai::future::Captured::<String>::default();
+        let builder = rsx! { div(id = "measured") {} }.with_post_build_async({
+            let id = id.clone();
+            move |dom: SsrDom| async move {
+                let value = dom.get_attrib("id")?.unwrap_or_default();
+                id.send(value).await?;
+                Ok(())
+            }
+        });
+        let ssr = SsrDom::try_from(builder).unwrap();
+        futures_lite::future::block_on(ssr.executor.run(async {
+            assert_eq!(id.get().await, "measured");
+        }));
+    }
+
+    #[test]
+    fn ssr_set_text_reuses_buffer() {
+        let executor = std::sync::Arc::new(async_executor::Executor::default());
+        let text = SsrDom::text(executor, "clicked 10 times");
+        let buffer_ptr = |text: &SsrDom| match &*text.node.try_read().unwrap() {
+            super::SsrNode::Text(s) => s.as_ptr(),
+            _ => panic!("not a text node"),
+        };
+        let before = buffer_ptr(&text);
+        text.set_text("clicked 10 times").unwrap();
+        assert_eq!(buffer_ptr(&text), before);
+        text.set_text("clicked 1 time").unwrap();
+        assert_eq!(buffer_ptr(&text), before);
+        assert_eq!(text.get_text().unwrap(), "clicked 1 time");
+    }
+
+    #[test]
+    fn ssr_class_toggle() {
+        let (tx, rx) = mogwai::channel::mpsc::bounded::<bool>(1);
+        let ssr = SsrDom::try_from(rsx! {
+            a(class:active = rx, class = "nav-link shown", class:disabled = false) {}
+        })
+        .unwrap();
+        futures_lite::future::block_on(async {
+            assert_eq!(
+                ssr.html_string().await,
+                r#"<a class="nav-link shown"></a>"#
+            );
+            for (on, expected) in [
+                (true, r#"<a class="nav-link shown active"></a>"#),
+                (true, r#"<a class="nav-link shown active"></a>"#),
+                (false, r#"<a class="nav-link shown"></a>"#),
+            ] {
+                tx.send(on).await.unwrap();
+                ssr.wait_for_steady_state().await;
+                assert_eq!(ssr.html_string().await, expected);
+            }
+        });
+    }
+
+    #[test]
+    fn ssr_optional_style_stream() {
+        let (tx, rx) = mogwai::channel::mpsc::bounded::<Option<String>>(1);
+        let ssr =
+            SsrDom::try_from(rsx! { div(style:color = "red", style:float = ("left", rx)) {} })
+                .unwrap();
+        futures_lite::future::block_on(async {
+            assert_eq!(
+                ssr.html_string().await,
+                r#"<div style="color: red; float: left;"></div>"#
+            );
+            for (value, expected) in [
+                (None, r#"<div style="color: red;"></div>"#),
+                (Some("right"), r#"<div style="color: red; float: right;"></div>"#),
+                (Some(""), r#"<div style="color: red;"></div>"#),
+            ] {
+                tx.send(value.map(String::from)).await.unwrap();
+                ssr.wait_for_steady_state().await;
+                assert_eq!(ssr.html_string().await, expected);
+            }
+        });
+    }
+
+    #[test]
+    fn ssr_optional_attrib_stream() {
+        let (tx, rx) = mogwai::channel::mpsc::bounded::<Option<String>>(1);
+        let ssr = SsrDom::try_from(
+            ViewBuilder::element("button").with_optional_attrib_stream("aria-pressed", rx),
+        )
+        .unwrap();
+        futures_lite::future::block_on(ssr.executor.run(async {
+            assert_eq!(ssr.html_string().await, "<button></button>");
+            for (value, expected) in [
+                (Some("true"), r#"<button aria-pressed="true"></button>"#),
+                (None, "<button></button>"),
+                (Some("false"), r#"<button aria-pressed="false"></button>"#),
+            ] {
+                tx.send(value.map(String::from)).await.unwrap();
+                let patched = mogwai::time::repeat_times(0.1, 10, || async {
+                    ssr.html_string().await == expected
+                })
+                .await;
+                assert!(patched.is_ok(), "{}", ssr.html_string().await);
+            }
+        }));
+
+        let ssr = SsrDom::try_from(
+            ViewBuilder::element("button")
+                .with_optional_attrib_stream("aria-pressed", Some("true".to_string())),
+        )
+        .unwrap();
+        assert_eq!(
+            futures_lite::future::block_on(ssr.html_string()),
+            r#"<button aria-pressed="true"></button>"#
+        );
+    }
+
+    #[test]
+    fn ssr_parent_and_children() {
+        let dom = Dom::from(
+            SsrDom::try_from(rsx! {
+                ul() {
+                    li() {"one"}
+                    li() { "two" b() {"!"} }
+                }
+            })
+            .unwrap(),
+        );
+        assert!(dom.parent().is_none());
+
+        let items = dom.children();
+        assert_eq!(items.len(), 2);
+        let two = items[1].children();
+        assert_eq!(two.len(), 2, "text nodes are children too");
+        assert_eq!(futures_lite::future::block_on(two[0].html_string()), "two");
+        assert!(two[0].children().is_empty());
+
+        let parent = two[1].parent().unwrap().parent().unwrap();
+        assert!(std::sync::Arc::ptr_eq(
+            &parent.as_either_ref().right().unwrap().node,
+            &dom.as_either_ref().right().unwrap().node
+        ));
+    }
 }
@@ -0,0 +1,106 @@
+//! Reading the files selected with `<input type="file">` elements.
+//!
+//! See [`JsDom::on_file_select`](crate::view::JsDom::on_file_select).
+use std::future::Future;
+
+use anyhow::Context;
+use send_wrapper::SendWrapper;
+use wasm_bindgen::{prelude::Closure, JsCast, JsValue};
+
+use super::first_result;
+
+/// A file selected by the user.
+#[derive(Clone, Debug)]
+pub struct SelectedFile {
+    file: SendWrapper<web_sys::File>,
+}
+
+impl From<web_sys::File> for SelectedFile {
+    fn from(file: web_sys::File) -> Self {
+        SelectedFile {
+            file: SendWrapper::new(file),
+        }
+    }
+}
+
+impl SelectedFile {
+    /// The name of the file, without its path.
+    pub fn name(&self) -> String {
+        self.file.name()
+    }
+
+    /// The size of the file in bytes.
+    pub fn size(&self) -> u64 {
+        self.file.size() as u64
+    }
+
+    /// Read the contents of the file as text.
+    pub fn read_text(&self) -> impl Future<Output = anyhow::Result<String>> + Send {
+        let contents = self.read(|reader, file| reader.read_as_text(file));
+        async move {
+            contents
+                .await?
+                .as_string()
+                .context("file contents are not text")
+        }
+    }
+
+    /// Read the contents of the file as bytes.
+    pub fn read_bytes(&self) -> impl Future<Output = anyhow::Result<Vec<u8>>> + Send {
+        let contents = self.read(|reader, file| reader.read_as_array_buffer(file));
+        async move { Ok(js_sys::Uint8Array::new(&*contents.await?).to_vec()) }
+    }
+
+    /// Start reading the file with a [`web_sys::FileReader`], returning a
+    /// future of the reader's result.
+    fn read(
+        &self,
+        start: impl FnOnce(&web_sys::FileReader, &web_sys::File) -> Result<(), JsValue>,
+    ) -> impl Future<Output = anyhow::Result<SendWrapper<JsValue>>> + Send {
+        let name = self.name();
+        let (send, result) = first_result::<SendWrapper<JsValue>>();
+        let reading = web_sys::FileReader::new().and_then(|reader| {
+            let send_error = send.clone();
+            let on_load = Closure::wrap(Box::new({
+                let reader = reader.clone();
+                move |_: JsValue| {
+                    send(
+                        reader
+                            .result()
+                            .map(SendWrapper::new)
+                            .map_err(|e| anyhow::anyhow!("no result: {:#?}", e)),
+                    )
+                }
+            }) as Box<dyn FnMut(JsValue)>);
+            let on_error = Closure::wrap(Box::new(move |_: JsValue| {
+                send_error(Err(anyhow::anyhow!("the read failed")))
+            }) as Box<dyn FnMut(JsValue)>);
+            reader.set_onload(Some(on_load.as_ref().unchecked_ref()));
+            reader.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+            start(&reader, &self.file)?;
+            Ok(SendWrapper::new((reader, on_load, on_error)))
+        });
+        async move {
+            let reading = reading
+                .map_err(|e| anyhow::anyhow!("{:#?}", e))
+                .with_context(|| format!("could not read '{}'", name))?;
+            let contents = result
+                .await
+                .with_context(|| format!("could not read '{}'", name))?;
+            drop(reading);
+            Ok(contents)
+        }
+    }
+}
+
+/// Returns the files currently selected in the given input.
+pub(crate) fn selected_files(input: &web_sys::HtmlInputElement) -> Vec<SelectedFile> {
+    let files = match input.files() {
+        Some(files) => files,
+        None => return vec![],
+    };
+    (0..files.length())
+        .filter_map(|i| files.get(i))
+        .map(SelectedFile::from)
+        .collect()
+}
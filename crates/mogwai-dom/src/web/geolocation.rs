@@ -0,0 +1,152 @@
+//! Futures and streams of the device's position from
+//! [`web_sys::Geolocation`].
+use std::{future::Future, pin::Pin};
+
+use mogwai::{channel::mpsc, stream::Stream};
+use send_wrapper::SendWrapper;
+use wasm_bindgen::{prelude::Closure, JsCast, JsValue};
+
+use super::first_result;
+use crate::utils::WINDOW;
+
+/// A geographic position.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Coords {
+    /// Latitude in decimal degrees.
+    pub latitude: f64,
+    /// Longitude in decimal degrees.
+    pub longitude: f64,
+    /// Accuracy of the latitude and longitude in meters.
+    pub accuracy: f64,
+    /// Altitude in meters above the WGS84 ellipsoid, if available.
+    pub altitude: Option<f64>,
+    /// Direction of travel in degrees clockwise from true north, if available.
+    pub heading: Option<f64>,
+    /// Ground speed in meters per second, if available.
+    pub speed: Option<f64>,
+}
+
+impl From<web_sys::Coordinates> for Coords {
+    fn from(coords: web_sys::Coordinates) -> Self {
+        Coords {
+            latitude: coords.latitude(),
+            longitude: coords.longitude(),
+            accuracy: coords.accuracy(),
+            altitude: coords.altitude(),
+            heading: coords.heading(),
+            speed: coords.speed(),
+        }
+    }
+}
+
+type Callback = Closure<dyn FnMut(JsValue)>;
+
+fn geolocation() -> anyhow::Result<web_sys::Geolocation> {
+    WINDOW
+        .with(|w| w.navigator().geolocation())
+        .map_err(|e| anyhow::anyhow!("geolocation is not available: {:#?}", e))
+}
+
+/// Create the success and error callbacks expected by the geolocation API,
+/// which send their converted results to the given function.
+fn callbacks(send: impl Fn(anyhow::Result<Coords>) + Clone + 'static) -> (Callback, Callback) {
+    let send_error = send.clone();
+    let on_position = Closure::wrap(Box::new(move |val: JsValue| {
+        let coords = val
+            .dyn_into::<web_sys::Position>()
+            .map(|position| Coords::from(position.coords()))
+            .map_err(|val| anyhow::anyhow!("not a position: {:#?}", val));
+        send(coords);
+    }) as Box<dyn FnMut(JsValue)>);
+    let on_error = Closure::wrap(Box::new(move |val: JsValue| {
+        let msg = val
+            .dyn_ref::<web_sys::PositionError>()
+            .map(|err| err.message())
+            .unwrap_or_else(|| format!("{:#?}", val));
+        send_error(Err(anyhow::anyhow!("could not get position: {}", msg)));
+    }) as Box<dyn FnMut(JsValue)>);
+    (on_position, on_error)
+}
+
+/// Get the device's current position.
+pub fn current() -> impl Future<Output = anyhow::Result<Coords>> {
+    let (send, result) = first_result::<Coords>();
+    let (on_position, on_error) = callbacks(send);
+    let requested = geolocation().and_then(|geo| {
+        geo.get_current_position_with_error_callback(
+            on_position.as_ref().unchecked_ref(),
+            Some(on_error.as_ref().unchecked_ref()),
+        )
+        .map_err(|e| anyhow::anyhow!("could not request position: {:#?}", e))
+    });
+    async move {
+        requested?;
+        let coords = result.await;
+        drop((on_position, on_error));
+        coords
+    }
+}
+
+/// Watch the device's position, producing a stream of updates.
+///
+/// The watch is cleared when the stream is dropped.
+pub fn watch() -> impl Stream<Item = anyhow::Result<Coords>> + Send {
+    let (tx, rx) = mpsc::unbounded::<anyhow::Result<Coords>>();
+    let (on_position, on_error) = callbacks(move |result| {
+        let _ = tx.try_send(result);
+    });
+    let watch = geolocation().and_then(|geo| {
+        let id = geo
+            .watch_position_with_error_callback(
+                on_position.as_ref().unchecked_ref(),
+                Some(on_error.as_ref().unchecked_ref()),
+            )
+            .map_err(|e| anyhow::anyhow!("could not watch position: {:#?}", e))?;
+        Ok((geo, id))
+    });
+    let (watch, error) = match watch {
+        Ok(watch) => (Some(SendWrapper::new(watch)), None),
+        Err(e) => (None, Some(e)),
+    };
+
+    struct Watch {
+        watch: Option<SendWrapper<(web_sys::Geolocation, i32)>>,
+        _callbacks: SendWrapper<(Callback, Callback)>,
+        error: Option<anyhow::Error>,
+        rx: mpsc::Receiver<anyhow::Result<Coords>>,
+    }
+
+    impl Drop for Watch {
+        fn drop(&mut self) {
+            if let Some(watch) = self.watch.take() {
+                let (geo, id) = watch.take();
+                geo.clear_watch(id);
+            }
+        }
+    }
+
+    impl Stream for Watch {
+        type Item = anyhow::Result<Coords>;
+
+        fn poll_next(
+            self: Pin<&mut Self>,
+            cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<Option<Self::Item>> {
+            let this = self.get_mut();
+            if let Some(e) = this.error.take() {
+                return std::task::Poll::Ready(Some(Err(e)));
+            }
+            if this.watch.is_none() {
+                return std::task::Poll::Ready(None);
+            }
+            Pin::new(&mut this.rx).poll_next(cx)
+        }
+    }
+
+    Watch {
+        watch,
+        _callbacks: SendWrapper::new((on_position, on_error)),
+        error,
+        rx,
+    }
+}
@@ -0,0 +1,206 @@
+//! A minimal key-value store on top of [IndexedDB].
+//!
+//! Values are serialized as JSON with [`serde_json`].
+//!
+//! Requires the `idb` feature.
+//!
+//! ```rust,no_run
+//! # async fn demo() -> anyhow::Result<()> {
+//! let store = mogwai_dom::web::idb::open("my-app", "settings").await?;
+//! store.put("volume", &11u8).await?;
+//! assert_eq!(store.get::<u8>("volume").await?, Some(11));
+//! store.delete("volume").await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [IndexedDB]: https://developer.mozilla.org/en-US/docs/Web/API/IndexedDB_API
+use std::future::Future;
+
+use anyhow::Context;
+use send_wrapper::SendWrapper;
+use serde::{de::DeserializeOwned, Serialize};
+use wasm_bindgen::{prelude::Closure, JsCast, JsValue};
+
+use super::first_result;
+use crate::utils::WINDOW;
+
+type Callback = Closure<dyn FnMut(JsValue)>;
+
+/// An object store in an IndexedDB database.
+#[derive(Clone, Debug)]
+pub struct Store {
+    db: SendWrapper<web_sys::IdbDatabase>,
+    name: String,
+}
+
+/// Open the object store `store` in the database `db`, creating either if
+/// they don't exist.
+pub async fn open(db: &str, store: &str) -> anyhow::Result<Store> {
+    let factory = WINDOW
+        .with(|w| w.indexed_db())
+        .ok()
+        .flatten()
+        .context("IndexedDB is not available")?;
+    let factory = SendWrapper::new(factory);
+
+    let database = open_with_store(&factory, db, None, store).await?;
+    if database.object_store_names().contains(store) {
+        return Ok(Store {
+            db: database,
+            name: store.to_string(),
+        });
+    }
+
+    // the database exists without the store, which can only be created
+    // during an upgrade to a new version
+    let version = database.version() as u32 + 1;
+    database.close();
+    let database = open_with_store(&factory, db, Some(version), store).await?;
+    Ok(Store {
+        db: database,
+        name: store.to_string(),
+    })
+}
+
+/// Open the database, creating the store if the database is upgraded.
+fn open_with_store(
+    factory: &web_sys::IdbFactory,
+    db: &str,
+    version: Option<u32>,
+    store: &str,
+) -> impl Future<Output = anyhow::Result<SendWrapper<web_sys::IdbDatabase>>> + Send {
+    let opening = match version {
+        Some(version) => factory.open_with_u32(db, version),
+        None => factory.open(db),
+    }
+    .map_err(|e| anyhow::anyhow!("could not open '{}': {:#?}", db, e))
+    .map(|request| {
+        let on_upgrade = Closure::wrap(Box::new({
+            let request = request.clone();
+            let store = store.to_string();
+            move |_: JsValue| {
+                let created = request
+                    .result()
+                    .and_then(|db| db.dyn_into::<web_sys::IdbDatabase>().map_err(JsValue::from))
+                    .and_then(|db| {
+                        if !db.object_store_names().contains(&store) {
+                            db.create_object_store(&store)?;
+                        }
+                        Ok(())
+                    });
+                if let Err(e) = created {
+                    log::error!("could not create object store '{}': {:#?}", store, e);
+                }
+            }
+        }) as Box<dyn FnMut(JsValue)>);
+        request.set_onupgradeneeded(Some(on_upgrade.as_ref().unchecked_ref()));
+        let result = request_result(&request);
+        (SendWrapper::new(on_upgrade), result)
+    });
+    async move {
+        let (on_upgrade, result) = opening?;
+        let db = result.await?;
+        drop(on_upgrade);
+        let db = db
+            .take()
+            .dyn_into::<web_sys::IdbDatabase>()
+            .map_err(|e| anyhow::anyhow!("not a database: {:#?}", e))?;
+        Ok(SendWrapper::new(db))
+    }
+}
+
+/// Returns a future of the result of the given request.
+fn request_result(
+    request: &web_sys::IdbRequest,
+) -> impl Future<Output = anyhow::Result<SendWrapper<JsValue>>> + Send {
+    let (send, result) = first_result::<SendWrapper<JsValue>>();
+    let send_error = send.clone();
+    let on_success = Closure::wrap(Box::new({
+        let request = request.clone();
+        move |_: JsValue| {
+            send(
+                request
+                    .result()
+                    .map(SendWrapper::new)
+                    .map_err(|e| anyhow::anyhow!("no result: {:#?}", e)),
+            )
+        }
+    }) as Box<dyn FnMut(JsValue)>);
+    let on_error = Closure::wrap(Box::new({
+        let request = request.clone();
+        move |_: JsValue| {
+            let msg = request
+                .error()
+                .ok()
+                .flatten()
+                .map(|e| e.message())
+                .unwrap_or_else(|| "unknown error".to_string());
+            send_error(Err(anyhow::anyhow!("request failed: {}", msg)))
+        }
+    }) as Box<dyn FnMut(JsValue)>);
+    request.set_onsuccess(Some(on_success.as_ref().unchecked_ref()));
+    request.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+    let callbacks: SendWrapper<(Callback, Callback)> = SendWrapper::new((on_success, on_error));
+    async move {
+        let result = result.await;
+        drop(callbacks);
+        result
+    }
+}
+
+impl Store {
+    /// Start a request on the object store in a new transaction.
+    fn request(
+        &self,
+        mode: web_sys::IdbTransactionMode,
+        f: impl FnOnce(&web_sys::IdbObjectStore) -> Result<web_sys::IdbRequest, JsValue>,
+    ) -> impl Future<Output = anyhow::Result<SendWrapper<JsValue>>> + Send {
+        let requested = self
+            .db
+            .transaction_with_str_and_mode(&self.name, mode)
+            .and_then(|tx| tx.object_store(&self.name))
+            .and_then(|store| f(&store))
+            .map(|request| request_result(&request))
+            .map_err(|e| anyhow::anyhow!("could not start request: {:#?}", e));
+        async move { requested?.await }
+    }
+
+    /// Get the value stored at `key`, if any.
+    pub async fn get<T: DeserializeOwned>(&self, key: &str) -> anyhow::Result<Option<T>> {
+        let key = JsValue::from_str(key);
+        let value = self
+            .request(web_sys::IdbTransactionMode::Readonly, move |store| {
+                store.get(&key)
+            })
+            .await?;
+        if value.is_undefined() {
+            return Ok(None);
+        }
+        let json = value.as_string().context("stored value is not a string")?;
+        Ok(Some(serde_json::from_str(&json)?))
+    }
+
+    /// Store `value` at `key`, replacing any previous value.
+    pub async fn put<T: Serialize>(&self, key: &str, value: &T) -> anyhow::Result<()> {
+        let key = JsValue::from_str(key);
+        let value = JsValue::from_str(&serde_json::to_string(value)?);
+        let _ = self
+            .request(web_sys::IdbTransactionMode::Readwrite, move |store| {
+                store.put_with_key(&value, &key)
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Remove the value stored at `key`, if any.
+    pub async fn delete(&self, key: &str) -> anyhow::Result<()> {
+        let key = JsValue::from_str(key);
+        let _ = self
+            .request(web_sys::IdbTransactionMode::Readwrite, move |store| {
+                store.delete(&key)
+            })
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+//! Components that are only built once they near the viewport.
+use std::sync::{Arc, Mutex};
+
+use mogwai::{
+    channel::mpsc,
+    patch::ListPatch,
+    stream::StreamExt,
+    view::{AnyView, PinBoxStream, PostBuild, ViewBuilder},
+};
+
+use crate::view::{JsDom, SsrDom};
+
+/// How close to the viewport a lazy component is built, in CSS margin syntax.
+const ROOT_MARGIN: &str = "200px";
+
+/// Create a `div` that shows `placeholder` until it is about to scroll into
+/// view, at which point the component created by `build` replaces it.
+///
+/// This avoids building heavy components below the fold until they are
+/// needed. Nearness to the viewport is observed with an
+/// [`IntersectionObserver`](web_sys::IntersectionObserver), see
+/// [`JsDom::on_intersect`]. When rendered server-side the component is built
+/// immediately, as there is no viewport.
+///
+/// ```rust
+/// use mogwai_dom::prelude::*;
+///
+/// let comments = mogwai_dom::web::lazy_component(
+///     rsx! { p() {"Loading comments..."} },
+///     || rsx! { ul() { li() {"First!"} } },
+/// );
+/// let ssr = SsrDom::try_from(comments).unwrap();
+/// let html = futures_lite::future::block_on(ssr.html_string());
+/// assert_eq!(html, "<div><ul><li>First!</li></ul></div>");
+/// ```
+pub fn lazy_component(
+    placeholder: ViewBuilder,
+    build: impl FnOnce() -> ViewBuilder + Send + 'static,
+) -> ViewBuilder {
+    let build = Arc::new(Mutex::new(Some(build)));
+    let take_build = move || build.lock().ok()?.take();
+    let (tx, rx) = mpsc::bounded::<PinBoxStream<bool>>(1);
+
+    let near_viewport = {
+        let take_build = take_build.clone();
+        async move {
+            let mut intersections = rx.recv().await.ok()?;
+            while let Some(intersecting) = intersections.next().await {
+                if intersecting {
+                    return take_build().map(|build| ListPatch::replace(0, build()));
+                }
+            }
+            None
+        }
+    };
+    let mut builder = ViewBuilder::element("div")
+        .append(placeholder)
+        .with_child_stream(
+            futures_lite::stream::once_future(near_viewport).filter_map(|patch| patch),
+        );
+    builder
+        .post_build_ops
+        .push(Box::new(move |view: &mut AnyView| {
+            if let Some(js) = view.downcast_ref::<JsDom>() {
+                let _ = tx.try_send(Box::pin(js.on_intersect(ROOT_MARGIN)));
+            } else if let Some(ssr) = view.downcast_ref::<SsrDom>() {
+                if let Some(build) = take_build() {
+                    ssr.replace_children(vec![build()])?;
+                }
+            }
+            Ok(())
+        }) as PostBuild);
+    builder
+}
@@ -0,0 +1,47 @@
+//! Lazily loaded images that "blur up" from a low quality placeholder.
+use mogwai::{channel::mpsc, stream::StreamExt, view::ViewBuilder};
+
+use crate::view::DomEvent;
+
+/// Styles applied to the image while the real image is loading.
+const LOADING_STYLE: &str = "background-size: cover; filter: blur(8px); transition: filter 0.3s";
+
+/// Styles applied to the image once the real image has loaded.
+const LOADED_STYLE: &str = "background-image: none; filter: none";
+
+/// Create an `img` builder that loads `src` lazily, showing a blurred
+/// placeholder until the image loads.
+///
+/// The image is given `loading="lazy"`, so the browser defers fetching `src`
+/// until the image nears the viewport. In the meantime `placeholder` is
+/// shown blurred as the image's background. `placeholder` is typically a
+/// small `data:` URI, so it is available immediately. When the image's
+/// `load` event fires the placeholder is removed and the blur transitions
+/// away.
+///
+/// ```rust
+/// use mogwai_dom::prelude::*;
+///
+/// let img = SsrDom::try_from(mogwai_dom::web::lazy_image(
+///     "/photo.jpg",
+///     "data:image/png;base64,iVBORw0KGgo=",
+/// ))
+/// .unwrap();
+/// let html = futures_lite::future::block_on(img.html_string());
+/// assert!(html.contains(r#"loading="lazy""#));
+/// ```
+pub fn lazy_image(src: impl Into<String>, placeholder: impl Into<String>) -> ViewBuilder {
+    let (tx_load, rx_load) = mpsc::bounded::<DomEvent>(1);
+    ViewBuilder::element("img")
+        .with_single_attrib_stream("loading", "lazy")
+        .with_single_attrib_stream("src", src.into())
+        .with_single_style_stream(
+            "background-image",
+            format!("url(\"{}\")", placeholder.into()),
+        )
+        .with_style_stream((
+            LOADING_STYLE,
+            rx_load.take(1).map(|_| LOADED_STYLE.to_string()),
+        ))
+        .with_event("load", "myself", tx_load)
+}
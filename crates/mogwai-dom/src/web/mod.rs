@@ -0,0 +1,61 @@
+//! Helpers for browser APIs that are commonly needed by web apps.
+use std::future::Future;
+
+use anyhow::Context;
+use mogwai::channel::mpsc;
+
+pub mod file;
+pub mod geolocation;
+#[cfg(feature = "idb")]
+pub mod idb;
+mod lazy_component;
+mod lazy_image;
+pub mod navigator;
+mod print;
+mod roving_focus;
+pub mod scroll_restoration;
+mod share;
+
+pub use lazy_component::lazy_component;
+pub use lazy_image::lazy_image;
+pub use print::{after_print, before_print, print};
+pub use roving_focus::{roving_focus, Orientation};
+pub use share::{share, ShareData};
+
+/// Returns a function to send results with, and a future that resolves to
+/// the first result sent.
+///
+/// The future resolves to an error if the function is dropped before
+/// sending.
+pub(crate) fn first_result<T: Send + Sync + 'static>() -> (
+    impl Fn(anyhow::Result<T>) + Clone + 'static,
+    impl Future<Output = anyhow::Result<T>> + Send,
+) {
+    let (tx, rx) = mpsc::bounded::<anyhow::Result<T>>(1);
+    let send = move |result| {
+        // only the first result is kept
+        let _ = tx.try_send(result);
+    };
+    let result = async move { rx.recv().await.context("callback was dropped")? };
+    (send, result)
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn first_callback_result_resolves_future() {
+        let (send, result) = first_result::<u32>();
+        send(Ok(1));
+        send(Ok(2));
+        assert_eq!(futures_lite::future::block_on(result).unwrap(), 1);
+    }
+
+    #[test]
+    fn dropped_callback_resolves_future_with_error() {
+        let (send, result) = first_result::<u32>();
+        drop(send);
+        assert!(futures_lite::future::block_on(result).is_err());
+    }
+}
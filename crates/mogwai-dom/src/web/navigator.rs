@@ -0,0 +1,32 @@
+//! Feature detection and status from the browser's [`web_sys::Navigator`].
+use mogwai::stream::{Stream, StreamExt};
+use wasm_bindgen::JsValue;
+
+use crate::{event::event_stream, utils::WINDOW};
+
+/// Returns whether the browser is online.
+pub fn is_online() -> bool {
+    WINDOW.with(|w| w.navigator().on_line())
+}
+
+/// Returns a stream of the browser's online status.
+///
+/// Yields `true` each time the window's `online` event fires and `false`
+/// each time its `offline` event fires. Use [`is_online`] for the status
+/// at the time of subscription.
+pub fn online_status() -> impl Stream<Item = bool> + Send {
+    let (online, offline) = WINDOW.with(|w| {
+        (
+            event_stream("online", w).map(|_| true),
+            event_stream("offline", w).map(|_| false),
+        )
+    });
+    online.or(offline)
+}
+
+/// Returns whether the Web Share API (`navigator.share`) is available.
+pub fn can_share() -> bool {
+    WINDOW.with(|w| {
+        js_sys::Reflect::has(&w.navigator(), &JsValue::from_str("share")).unwrap_or(false)
+    })
+}
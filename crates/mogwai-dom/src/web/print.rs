@@ -0,0 +1,33 @@
+//! Printing the page, and adjusting views for print.
+use mogwai::stream::{Stream, StreamExt};
+
+use crate::{event::event_stream, utils::WINDOW};
+
+/// Open the browser's print dialog, resolving once printing is done or the
+/// dialog is dismissed.
+///
+/// Completion is signaled by the window's `afterprint` event.
+pub async fn print() {
+    let mut after = after_print();
+    if let Err(e) = WINDOW.with(|w| w.print()) {
+        log::error!("could not print: {:#?}", e);
+        return;
+    }
+    let _ = after.next().await;
+}
+
+/// Returns a stream that yields each time the page is about to be printed,
+/// from the window's `beforeprint` event.
+///
+/// Use this to adjust views for printing.
+pub fn before_print() -> impl Stream<Item = ()> + Send {
+    WINDOW.with(|w| event_stream("beforeprint", w).map(|_| ()))
+}
+
+/// Returns a stream that yields each time the page has been printed or the
+/// print dialog was dismissed, from the window's `afterprint` event.
+///
+/// Use this to restore views adjusted with [`before_print`].
+pub fn after_print() -> impl Stream<Item = ()> + Send {
+    WINDOW.with(|w| event_stream("afterprint", w).map(|_| ()))
+}
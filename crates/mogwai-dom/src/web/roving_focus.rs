@@ -0,0 +1,125 @@
+//! Roving focus for keyboard navigation among the items of lists and menus.
+use wasm_bindgen::JsCast;
+
+use crate::{event::add_event_callback, view::JsDom};
+
+/// The direction in which the items of a [`roving_focus`] container are laid
+/// out, which determines the arrow keys that move focus.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Orientation {
+    /// `ArrowLeft` and `ArrowRight` move focus.
+    Horizontal,
+    /// `ArrowUp` and `ArrowDown` move focus.
+    Vertical,
+}
+
+impl Orientation {
+    fn keys(&self) -> (&'static str, &'static str) {
+        match self {
+            Orientation::Horizontal => ("ArrowLeft", "ArrowRight"),
+            Orientation::Vertical => ("ArrowUp", "ArrowDown"),
+        }
+    }
+}
+
+/// Returns the index of the item containing `node`, if any.
+fn item_index(items: &web_sys::HtmlCollection, node: &web_sys::Node) -> Option<u32> {
+    (0..items.length()).find(|i| {
+        items
+            .item(*i)
+            .map(|item| item.contains(Some(node)))
+            .unwrap_or_default()
+    })
+}
+
+/// Make `index` the only item in the tab order, optionally focusing it.
+fn rove(items: &web_sys::HtmlCollection, index: u32, focus: bool) {
+    for i in 0..items.length() {
+        if let Some(item) = items.item(i) {
+            let tabindex = if i == index { "0" } else { "-1" };
+            let _ = item.set_attribute("tabindex", tabindex);
+            if i == index && focus {
+                if let Some(item) = item.dyn_ref::<web_sys::HtmlElement>() {
+                    let _ = item.focus();
+                }
+            }
+        }
+    }
+}
+
+/// Manage focus among the child elements of a container with a "roving
+/// tabindex".
+///
+/// Only one child is in the tab order at a time, so tabbing into the
+/// container focuses the last focused child, and tabbing again leaves the
+/// container. The arrow keys of the given orientation move focus to the
+/// previous or next child, wrapping around at either end, while `Home` and
+/// `End` move focus to the first and last child.
+///
+/// This is meant to be used with the `post:build` [`rsx`](crate::rsx)
+/// macro attribute, or [`ViewBuilder::with_post_build`](mogwai::view::ViewBuilder::with_post_build).
+///
+/// ```rust, no_run
+/// use mogwai_dom::{prelude::*, web::Orientation};
+///
+/// let _ = rsx! {
+///     ul(role = "menu", post:build = mogwai_dom::web::roving_focus(Orientation::Vertical)) {
+///         li(role = "menuitem") {"Open"}
+///         li(role = "menuitem") {"Save"}
+///     }
+/// };
+/// ```
+pub fn roving_focus(
+    orientation: Orientation,
+) -> impl FnOnce(&mut JsDom) -> anyhow::Result<()> + Send + Sync + 'static {
+    move |dom: &mut JsDom| {
+        let container = dom
+            .clone_as::<web_sys::Element>()
+            .ok_or_else(|| anyhow::anyhow!("roving focus requires an element"))?;
+        let items = container.children();
+        rove(&items, 0, false);
+
+        let (prev, next) = orientation.keys();
+        let keydown = add_event_callback("keydown", &container, {
+            let items = items.clone();
+            move |ev: web_sys::Event| {
+                let current = ev
+                    .target()
+                    .and_then(|target| target.dyn_into::<web_sys::Node>().ok())
+                    .and_then(|target| item_index(&items, &target));
+                let (ev, current) = match (ev.dyn_ref::<web_sys::KeyboardEvent>(), current) {
+                    (Some(ev), Some(current)) => (ev, current),
+                    _ => return,
+                };
+                let len = items.length();
+                let key = ev.key();
+                let index = if key == prev {
+                    (current + len - 1) % len
+                } else if key == next {
+                    (current + 1) % len
+                } else if key == "Home" {
+                    0
+                } else if key == "End" {
+                    len - 1
+                } else {
+                    return;
+                };
+                ev.prevent_default();
+                rove(&items, index, true);
+            }
+        });
+        // keep the tab order in step with focus moved by other means, eg. clicks
+        let focusin = add_event_callback("focusin", &container, move |ev: web_sys::Event| {
+            let target = ev.target().and_then(|t| t.dyn_into::<web_sys::Node>().ok());
+            if let Some(index) = target.and_then(|target| item_index(&items, &target)) {
+                rove(&items, index, false);
+            }
+        });
+
+        dom.listener_callbacks
+            .try_write()
+            .ok_or_else(|| anyhow::anyhow!("cannot add roving focus listeners"))?
+            .extend([keydown, focusin]);
+        Ok(())
+    }
+}
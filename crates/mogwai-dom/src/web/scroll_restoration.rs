@@ -0,0 +1,51 @@
+//! Saving and restoring the window's scroll position.
+//!
+//! Single page apps that swap out their views on navigation lose the
+//! browser's native scroll restoration. Call [`save`] with a key for the
+//! current page (eg. its route) before navigating away, and [`restore`]
+//! with the same key after navigating back.
+use std::{cell::RefCell, collections::HashMap};
+
+use wasm_bindgen::UnwrapThrowExt;
+
+use crate::utils::WINDOW;
+
+thread_local! {
+    static OFFSETS: RefCell<HashMap<String, (f64, f64)>> = RefCell::new(HashMap::new());
+}
+
+/// Save the window's current scroll offset under the given key.
+///
+/// Any offset previously saved under the key is overwritten.
+pub fn save(key: impl Into<String>) {
+    let offset = WINDOW.with(|w| {
+        (
+            w.scroll_x().unwrap_throw(),
+            w.scroll_y().unwrap_throw(),
+        )
+    });
+    OFFSETS.with(|offsets| offsets.borrow_mut().insert(key.into(), offset));
+}
+
+/// Returns the scroll offset saved under the given key, if any.
+pub fn saved(key: &str) -> Option<(f64, f64)> {
+    OFFSETS.with(|offsets| offsets.borrow().get(key).copied())
+}
+
+/// Scroll the window to the offset saved under the given key.
+///
+/// Returns `false` if no offset has been saved under the key, in which case
+/// the window is not scrolled.
+pub fn restore(key: &str) -> bool {
+    if let Some((x, y)) = saved(key) {
+        WINDOW.with(|w| w.scroll_to_with_x_and_y(x, y));
+        true
+    } else {
+        false
+    }
+}
+
+/// Forget the scroll offset saved under the given key.
+pub fn forget(key: &str) {
+    OFFSETS.with(|offsets| offsets.borrow_mut().remove(key));
+}
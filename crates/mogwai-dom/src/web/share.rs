@@ -0,0 +1,73 @@
+//! Sharing content with the [Web Share API].
+//!
+//! [Web Share API]: https://developer.mozilla.org/en-US/docs/Web/API/Web_Share_API
+use std::future::Future;
+
+use wasm_bindgen::{JsCast, JsValue};
+
+use super::first_result;
+use crate::utils::WINDOW;
+
+/// The content to share with [`share`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ShareData {
+    /// The title of the shared content.
+    pub title: Option<String>,
+    /// The text of the shared content.
+    pub text: Option<String>,
+    /// A URL referring to the shared content.
+    pub url: Option<String>,
+}
+
+impl ShareData {
+    fn to_object(&self) -> Result<js_sys::Object, JsValue> {
+        let object = js_sys::Object::new();
+        for (key, value) in [
+            ("title", &self.title),
+            ("text", &self.text),
+            ("url", &self.url),
+        ] {
+            if let Some(value) = value {
+                js_sys::Reflect::set(&object, &key.into(), &value.into())?;
+            }
+        }
+        Ok(object)
+    }
+}
+
+/// Share `data` using the platform's native share dialog.
+///
+/// Resolves to an error if the browser does not support `navigator.share`
+/// (see [`can_share`](super::navigator::can_share)), if the page is not
+/// handling a user gesture, or if the user cancels the dialog.
+pub fn share(data: ShareData) -> impl Future<Output = anyhow::Result<()>> + Send {
+    let (send, result) = first_result::<()>();
+    match share_promise(&data) {
+        Ok(promise) => wasm_bindgen_futures::spawn_local(async move {
+            send(
+                wasm_bindgen_futures::JsFuture::from(promise)
+                    .await
+                    .map(|_| ())
+                    .map_err(|e| anyhow::anyhow!("could not share: {:#?}", e)),
+            )
+        }),
+        Err(e) => send(Err(e)),
+    }
+    result
+}
+
+fn share_promise(data: &ShareData) -> anyhow::Result<js_sys::Promise> {
+    let navigator = WINDOW.with(|w| w.navigator());
+    // `navigator.share` is not in web-sys' stable API, so it is called dynamically
+    let share = js_sys::Reflect::get(&navigator, &JsValue::from_str("share"))
+        .ok()
+        .and_then(|share| share.dyn_into::<js_sys::Function>().ok())
+        .ok_or_else(|| anyhow::anyhow!("the Web Share API is not supported"))?;
+    let data = data
+        .to_object()
+        .map_err(|e| anyhow::anyhow!("could not create share data: {:#?}", e))?;
+    share
+        .call1(&navigator, &data)
+        .and_then(|promise| promise.dyn_into::<js_sys::Promise>())
+        .map_err(|e| anyhow::anyhow!("could not share: {:#?}", e))
+}
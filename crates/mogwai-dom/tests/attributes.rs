@@ -0,0 +1,10 @@
+//! Compile-time checks of `rsx!` attribute names.
+#![cfg(feature = "validate-attributes")]
+
+#[test]
+fn rsx_attribute_names_are_validated() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/typo_attribute.rs");
+    t.pass("tests/ui/custom_attributes.rs");
+    t.pass("tests/ui/mogwai_attributes.rs");
+}
@@ -0,0 +1,12 @@
+use mogwai_dom::prelude::*;
+
+fn main() {
+    let _ = rsx! {
+        div(
+            class = "x",
+            data_thing = "a string",
+            aria_label = "label",
+            attr:foo = "bar",
+        ) {}
+    };
+}
@@ -0,0 +1,10 @@
+use mogwai_dom::prelude::*;
+
+fn main() {
+    let _ = rsx! {
+        ul() {
+            li(key = "1") {"one"}
+            li(key = String::from("2"), namespace = "http://www.w3.org/1999/xhtml") {"two"}
+        }
+    };
+}
@@ -0,0 +1,7 @@
+use mogwai_dom::prelude::*;
+
+fn main() {
+    let _ = rsx! {
+        div(clss = "x") {}
+    };
+}
@@ -0,0 +1,239 @@
+//! Known HTML and SVG attribute names, used to validate `rsx!` attributes
+//! when the `validate-attributes` feature is enabled.
+
+/// HTML attribute names, global and element specific.
+const HTML_ATTRIBUTES: &[&str] = &[
+    "accept",
+    "accept-charset",
+    "accesskey",
+    "action",
+    "align",
+    "allow",
+    "allowfullscreen",
+    "alt",
+    "as",
+    "async",
+    "autocapitalize",
+    "autocomplete",
+    "autofocus",
+    "autoplay",
+    "background",
+    "bgcolor",
+    "border",
+    "capture",
+    "charset",
+    "checked",
+    "cite",
+    "class",
+    "color",
+    "cols",
+    "colspan",
+    "content",
+    "contenteditable",
+    "controls",
+    "coords",
+    "crossorigin",
+    "csp",
+    "data",
+    "datetime",
+    "decoding",
+    "default",
+    "defer",
+    "dir",
+    "dirname",
+    "disabled",
+    "download",
+    "draggable",
+    "enctype",
+    "enterkeyhint",
+    "for",
+    "form",
+    "formaction",
+    "formenctype",
+    "formmethod",
+    "formnovalidate",
+    "formtarget",
+    "headers",
+    "height",
+    "hidden",
+    "high",
+    "href",
+    "hreflang",
+    "http-equiv",
+    "id",
+    "inert",
+    "inputmode",
+    "integrity",
+    "is",
+    "ismap",
+    "itemid",
+    "itemprop",
+    "itemref",
+    "itemscope",
+    "itemtype",
+    "kind",
+    "label",
+    "lang",
+    "list",
+    "loading",
+    "loop",
+    "low",
+    "max",
+    "maxlength",
+    "media",
+    "method",
+    "min",
+    "minlength",
+    "multiple",
+    "muted",
+    "name",
+    "nonce",
+    "novalidate",
+    "open",
+    "optimum",
+    "part",
+    "pattern",
+    "ping",
+    "placeholder",
+    "playsinline",
+    "popover",
+    "poster",
+    "preload",
+    "readonly",
+    "referrerpolicy",
+    "rel",
+    "required",
+    "reversed",
+    "role",
+    "rows",
+    "rowspan",
+    "sandbox",
+    "scope",
+    "selected",
+    "shape",
+    "size",
+    "sizes",
+    "slot",
+    "span",
+    "spellcheck",
+    "src",
+    "srcdoc",
+    "srclang",
+    "srcset",
+    "start",
+    "step",
+    "style",
+    "summary",
+    "tabindex",
+    "target",
+    "title",
+    "translate",
+    "type",
+    "usemap",
+    "value",
+    "width",
+    "wrap",
+];
+
+/// SVG attribute names that are not also HTML attribute names.
+const SVG_ATTRIBUTES: &[&str] = &[
+    "attributeName",
+    "baseFrequency",
+    "begin",
+    "clip-path",
+    "clip-rule",
+    "clipPathUnits",
+    "cx",
+    "cy",
+    "d",
+    "dominant-baseline",
+    "dur",
+    "dx",
+    "dy",
+    "fill",
+    "fill-opacity",
+    "fill-rule",
+    "filter",
+    "filterUnits",
+    "font-family",
+    "font-size",
+    "font-weight",
+    "fr",
+    "from",
+    "fx",
+    "fy",
+    "gradientTransform",
+    "gradientUnits",
+    "in",
+    "in2",
+    "marker-end",
+    "marker-mid",
+    "marker-start",
+    "markerHeight",
+    "markerUnits",
+    "markerWidth",
+    "mask",
+    "maskUnits",
+    "offset",
+    "opacity",
+    "orient",
+    "path",
+    "pathLength",
+    "patternContentUnits",
+    "patternTransform",
+    "patternUnits",
+    "points",
+    "preserveAspectRatio",
+    "r",
+    "refX",
+    "refY",
+    "repeatCount",
+    "result",
+    "rotate",
+    "rx",
+    "ry",
+    "stdDeviation",
+    "stop-color",
+    "stop-opacity",
+    "stroke",
+    "stroke-dasharray",
+    "stroke-dashoffset",
+    "stroke-linecap",
+    "stroke-linejoin",
+    "stroke-miterlimit",
+    "stroke-opacity",
+    "stroke-width",
+    "text-anchor",
+    "textLength",
+    "to",
+    "transform",
+    "values",
+    "vector-effect",
+    "version",
+    "viewBox",
+    "visibility",
+    "x",
+    "x1",
+    "x2",
+    "xmlns",
+    "y",
+    "y1",
+    "y2",
+];
+
+/// Attribute names that mogwai itself gives meaning to, eg. `key` which is
+/// read back when diffing keyed children.
+const MOGWAI_ATTRIBUTES: &[&str] = &["key", "namespace"];
+
+/// Returns whether the given attribute name is a known HTML, SVG or mogwai
+/// attribute.
+///
+/// Dashed names that aren't in the known set, like `data-*` and `aria-*`, are
+/// always accepted, as custom elements commonly use dashed attributes of
+/// their own.
+pub fn is_known_attribute(name: &str) -> bool {
+    name.contains('-')
+        || MOGWAI_ATTRIBUTES.contains(&name)
+        || HTML_ATTRIBUTES.contains(&name.to_lowercase().as_str())
+        || SVG_ATTRIBUTES.contains(&name)
+}
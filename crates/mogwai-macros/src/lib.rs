@@ -3,6 +3,8 @@
 use quote::quote;
 use syn::Error;
 
+#[cfg(feature = "validate-attributes")]
+mod attributes;
 mod tokens;
 use tokens::{AttributeToken, ViewToken};
 
@@ -33,15 +35,28 @@ fn combine_errors(errs: Vec<Error>) -> Option<Error> {
         })
 }
 
-fn node_to_builder_token_stream(
-    view_token: &ViewToken,
-) -> Result<proc_macro2::TokenStream, Error> {
+/// The path to the core mogwai crate, as seen from the macro's call site.
+fn mogwai_path() -> proc_macro2::TokenStream {
     #[cfg(feature = "dom")]
     let mogwai_path = quote! { mogwai_dom::core };
     #[cfg(feature = "pxy")]
     let mogwai_path = quote! { pxy_mogwai::core };
     #[cfg(not(any(feature = "dom", feature = "pxy")))]
     let mogwai_path = quote! { mogwai };
+    mogwai_path
+}
+
+/// Convert a view token into a `ViewBuilder` expression.
+///
+/// An element's namespace is given by its `xmlns` or `namespace` attribute, or
+/// the `xmlns:svg` shorthand. Child elements inherit the namespace of their
+/// parent unless they declare their own. `in_namespace` is whether an
+/// ancestor has declared one.
+fn node_to_builder_token_stream(
+    view_token: &ViewToken,
+    in_namespace: bool,
+) -> Result<proc_macro2::TokenStream, Error> {
+    let mogwai_path = mogwai_path();
     match view_token {
         ViewToken::Element {
             name,
@@ -50,17 +65,66 @@ fn node_to_builder_token_stream(
             children,
         } => {
             let may_xmlns = attributes.iter().find_map(|att| match att {
-                AttributeToken::Xmlns(expr) => Some(expr),
+                AttributeToken::Xmlns(expr) | AttributeToken::Namespace(expr) => Some(expr),
                 _ => None,
             });
-
+            let in_namespace = in_namespace || may_xmlns.is_some();
+            // bind the namespace once so it can be shared with descendants
+            let namespace_binding = may_xmlns.map(|ns| {
+                quote! { let __mogwai_rsx_namespace = #ns; }
+            });
+            // bind each `bind:` expression once, as its listener and its writer both use it
+            let mut bound = vec![];
             let mut errs = vec![];
-            let (attribute_tokens, attribute_errs) =
-                partition_unzip(attributes.iter(), AttributeToken::try_builder_token_stream);
+            let bind_bindings = attributes
+                .iter()
+                .filter_map(|att| match att {
+                    AttributeToken::Bind(property, expr) => {
+                        if bound.contains(&property) {
+                            errs.push(Error::new_spanned(
+                                expr,
+                                format!("`bind:{}` can only be used once per element", property),
+                            ));
+                        }
+                        bound.push(property);
+                        let ident = tokens::bind_ident(property);
+                        Some(quote! { let #ident = &#expr; })
+                    }
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+
+            // spreads are applied after the literal attributes so they can override them,
+            // and class toggles last so they modify the final `class` attribute
+            let (toggles, attributes): (Vec<_>, Vec<_>) = attributes
+                .iter()
+                .partition(|att| matches!(att, AttributeToken::ClassToggle(..)));
+            // a new `class` value would drop the toggled classes
+            if !toggles.is_empty() {
+                errs.extend(attributes.iter().filter_map(|att| match att {
+                    AttributeToken::Attrib(name, expr)
+                        if name == "class" && !tokens::is_str_lit(expr) =>
+                    {
+                        Some(Error::new_spanned(
+                            expr,
+                            "`class:<name>` toggles can only be combined with a string literal \
+                             `class`, as each new `class` value would drop the toggled classes",
+                        ))
+                    }
+                    _ => None,
+                }));
+            }
+            let (spreads, literals): (Vec<_>, Vec<_>) = attributes
+                .into_iter()
+                .partition(|att| matches!(att, AttributeToken::Spread(_)));
+            let (attribute_tokens, attribute_errs) = partition_unzip(
+                literals.into_iter().chain(spreads).chain(toggles),
+                AttributeToken::try_builder_token_stream,
+            );
             errs.extend(attribute_errs);
 
             let (child_tokens, child_errs) = partition_unzip(children.iter(), |token| {
-                node_to_builder_token_stream(token)
+                node_to_builder_token_stream(token, in_namespace)
             });
             let child_tokens = child_tokens.into_iter().map(|child| {
                 quote! {
@@ -73,12 +137,19 @@ fn node_to_builder_token_stream(
             if let Some(error) = may_error {
                 Err(error)
             } else {
-                let create = if let Some(ns) = may_xmlns {
-                    quote! {#mogwai_path::view::ViewBuilder::element_ns(#name, #ns)}
+                let create = if in_namespace {
+                    quote! {
+                        #mogwai_path::view::ViewBuilder::element_ns(
+                            #name,
+                            ::std::clone::Clone::clone(&__mogwai_rsx_namespace),
+                        )
+                    }
                 } else {
                     quote! {#mogwai_path::view::ViewBuilder::element(#name)}
                 };
                 Ok(quote! {{
+                    #namespace_binding
+                    #(#bind_bindings)*
                     #create
                         #(#attribute_tokens)*
                         #(#child_tokens)*
@@ -90,6 +161,42 @@ fn node_to_builder_token_stream(
             #[allow(unused_braces)]
             #expr
         }),
+        ViewToken::If {
+            condition,
+            then_branch,
+            else_branch,
+        } => {
+            let then_branch = branch_to_option_token_stream(then_branch, in_namespace)?;
+            let else_branch = match else_branch {
+                Some(branch) => branch_to_option_token_stream(branch, in_namespace)?,
+                None => quote! { ::std::option::Option::None },
+            };
+            Ok(quote! {
+                if #condition {
+                    #then_branch
+                } else {
+                    #else_branch
+                }
+            })
+        }
+    }
+}
+
+/// Convert one branch of an `if` node into an `Option<ViewBuilder>`
+/// expression.
+fn branch_to_option_token_stream(
+    view_token: &ViewToken,
+    in_namespace: bool,
+) -> Result<proc_macro2::TokenStream, Error> {
+    let tokens = node_to_builder_token_stream(view_token, in_namespace)?;
+    if let ViewToken::If { .. } = view_token {
+        // `else if` already results in an `Option`
+        Ok(tokens)
+    } else {
+        let mogwai_path = mogwai_path();
+        Ok(quote! {
+            ::std::option::Option::Some(#mogwai_path::view::ViewBuilder::from(#tokens))
+        })
     }
 }
 #[deprecated(since = "0.6", note = "Use `html` or convert to `rsx` instead")]
@@ -200,4 +307,91 @@ mod ssr_tests {
     fn can_parse_from_token_stream() {
         let _ts = proc_macro2::TokenStream::from_str(r#"<div class="any_class" />"#).unwrap();
     }
+
+    #[test]
+    fn typed_event_extracts_the_target_value() {
+        let typed: super::AttributeToken = syn::parse_str("on:input(value) = tx").unwrap();
+        assert!(matches!(typed, super::AttributeToken::OnValue(ref name, _) if name == "input"));
+        assert_eq!(
+            typed.try_builder_token_stream().is_ok(),
+            cfg!(feature = "dom")
+        );
+        let code = super::tokens::on_value_token_stream("input", &syn::parse_str("tx").unwrap())
+            .to_string();
+        assert!(code.contains("contra_filter_map"), "{}", code);
+        assert!(code.contains("mogwai_dom :: view :: DomEvent"), "{}", code);
+        assert!(code.contains("target_value ()"), "{}", code);
+
+        let untyped: super::AttributeToken = syn::parse_str("on:input = tx").unwrap();
+        assert!(matches!(untyped, super::AttributeToken::On(ref name, _) if name == "input"));
+
+        assert!(syn::parse_str::<super::AttributeToken>("on:input(checked) = tx").is_err());
+    }
+
+    #[test]
+    fn class_toggle_parses() {
+        let toggle: super::AttributeToken = syn::parse_str("class:is_active = active").unwrap();
+        assert!(
+            matches!(toggle, super::AttributeToken::ClassToggle(ref name, _) if name == "is-active")
+        );
+        let code = toggle.try_builder_token_stream().unwrap().to_string();
+        assert_eq!(code, ". with_class_toggle (\"is-active\" , active)");
+
+        let plain: super::AttributeToken = syn::parse_str("class = \"nav\"").unwrap();
+        assert!(matches!(plain, super::AttributeToken::Attrib(ref name, _) if name == "class"));
+    }
+
+    #[test]
+    fn class_toggle_rejects_a_changing_class() {
+        let element = |s: &str| -> super::ViewToken { syn::parse_str(s).unwrap() };
+        let to_builder = |s: &str| super::node_to_builder_token_stream(&element(s), false);
+
+        assert!(to_builder(r#"div(class = "nav", class:active = on) {}"#).is_ok());
+        assert!(to_builder(r#"div(class:active = on) {}"#).is_ok());
+        assert!(to_builder(r#"div(class = ("nav", classes), class:active = on) {}"#).is_err());
+        assert!(to_builder(r#"div(class = classes) {}"#).is_ok());
+    }
+
+    #[test]
+    fn bind_generates_listener_and_writer() {
+        let bind: super::AttributeToken = syn::parse_str("bind:value = name").unwrap();
+        assert!(matches!(bind, super::AttributeToken::Bind(ref p, _) if p == "value"));
+        assert_eq!(
+            bind.try_builder_token_stream().is_ok(),
+            cfg!(feature = "dom")
+        );
+        let code = super::tokens::bind_token_stream("value")
+            .unwrap()
+            .to_string();
+        assert!(code.contains(". with_event (\"input\""), "{}", code);
+        assert!(code.contains("target_value ()"), "{}", code);
+        assert!(code.contains(". with_capture_for_each"), "{}", code);
+        assert!(code.contains("set_property (\"value\""), "{}", code);
+        assert!(code.contains("__mogwai_rsx_bind_value"), "{}", code);
+
+        let code = super::tokens::bind_token_stream("checked")
+            .unwrap()
+            .to_string();
+        assert!(code.contains(". with_event (\"change\""), "{}", code);
+        assert!(code.contains("target_bool (\"checked\")"), "{}", code);
+
+        assert!(super::tokens::bind_token_stream("title").is_err());
+    }
+
+    #[test]
+    fn bind_evaluates_its_expression_once() {
+        let element: super::ViewToken =
+            syn::parse_str("input(bind:value = make_model()) {}").unwrap();
+        let result = super::node_to_builder_token_stream(&element, false);
+        if cfg!(feature = "dom") {
+            let code = result.unwrap().to_string();
+            assert_eq!(code.matches("make_model").count(), 1, "{}", code);
+        } else {
+            assert!(result.is_err());
+        }
+
+        let element: super::ViewToken =
+            syn::parse_str("input(bind:value = a, bind:value = b) {}").unwrap();
+        assert!(super::node_to_builder_token_stream(&element, false).is_err());
+    }
 }
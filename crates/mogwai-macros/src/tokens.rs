@@ -4,8 +4,9 @@ use std::convert::TryFrom;
 use proc_macro2::Span;
 use quote::quote;
 use syn::{
-    parse::Parse, punctuated::Punctuated, token, Error, Expr, Ident, LitStr,
-    Token,
+    parse::{discouraged::Speculative, Parse},
+    punctuated::Punctuated,
+    token, Error, Expr, Ident, LitStr, Token,
 };
 use syn_rsx::{Node, NodeType};
 
@@ -13,6 +14,95 @@ fn under_to_dash(s: impl AsRef<str>) -> String {
     s.as_ref().trim_matches('_').replace("_", "-")
 }
 
+/// The namespace used by the `xmlns:svg` shorthand.
+const SVG_NAMESPACE: &str = "http://www.w3.org/2000/svg";
+
+pub(crate) fn is_str_lit(expr: &Expr) -> bool {
+    matches!(
+        expr,
+        Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(_),
+            ..
+        })
+    )
+}
+
+fn is_int_lit(expr: &Expr) -> bool {
+    match expr {
+        Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(_),
+            ..
+        }) => true,
+        Expr::Unary(syn::ExprUnary {
+            op: syn::UnOp::Neg(_),
+            expr,
+            ..
+        }) => is_int_lit(expr),
+        _ => false,
+    }
+}
+
+/// The `.with_event` link of an `on:name(value)` attribute, which needs
+/// mogwai-dom's `DomEvent`.
+pub(crate) fn on_value_token_stream(name: &str, expr: &Expr) -> proc_macro2::TokenStream {
+    quote! {
+        .with_event(
+            #name,
+            "myself",
+            mogwai_dom::core::sink::SinkExt::contra_filter_map(
+                #expr,
+                |ev: mogwai_dom::view::DomEvent| ev.target_value(),
+            ),
+        )
+    }
+}
+
+/// The name that a reference to an element's `bind:property` expression is
+/// bound to, so the expression is only evaluated once.
+pub(crate) fn bind_ident(property: &str) -> syn::Ident {
+    quote::format_ident!("__mogwai_rsx_bind_{}", property)
+}
+
+/// The `.with_event` and `.with_capture_for_each` links of a `bind:property`
+/// attribute, which need mogwai-dom's `DomEvent` and `Dom`. Both links use
+/// the expression bound to [`bind_ident`].
+pub(crate) fn bind_token_stream(property: &str) -> Result<proc_macro2::TokenStream, Error> {
+    let (event, extract, ty) = match property {
+        "value" => ("input", quote! { ev.target_value() }, quote! { String }),
+        "checked" | "selected" => (
+            "change",
+            quote! { ev.target_bool(#property) },
+            quote! { bool },
+        ),
+        _ => {
+            return Err(Error::new(
+                Span::call_site(),
+                format!(
+                    "unsupported binding `bind:{}`, expected `value`, `checked` or `selected`",
+                    property
+                ),
+            ))
+        }
+    };
+    let bind = bind_ident(property);
+    Ok(quote! {
+        .with_event(
+            #event,
+            "myself",
+            mogwai_dom::core::sink::SinkExt::contra_filter_map(
+                std::clone::Clone::clone(#bind),
+                |ev: mogwai_dom::view::DomEvent| #extract,
+            ),
+        )
+        .with_capture_for_each((
+            #bind.stream(),
+            |dom: &mogwai_dom::view::Dom, value: #ty| {
+                let _ = dom.set_property(#property, value);
+            },
+        ))
+    })
+}
+
 #[derive(Clone, Debug)]
 /// An enumeration of all supported attribute types.
 pub enum AttributeToken {
@@ -20,15 +110,23 @@ pub enum AttributeToken {
     CaptureView(syn::Expr),
     CaptureForEach(syn::Expr),
     Xmlns(syn::Expr),
+    Namespace(syn::Expr),
     Style(syn::Expr),
     StyleSingle(String, syn::Expr),
     On(String, syn::Expr),
+    OnValue(String, syn::Expr),
+    Bind(String, syn::Expr),
     Window(String, syn::Expr),
     Document(String, syn::Expr),
     BooleanSingle(String, syn::Expr),
     BooleanTrue(String),
+    ClassToggle(String, syn::Expr),
     PatchChildren(syn::Expr),
+    PatchChildrenReversed(syn::Expr),
+    TestId(syn::Expr),
+    TabIndex(syn::Expr),
     Attrib(String, syn::Expr),
+    Spread(syn::Expr),
 }
 
 impl TryFrom<syn_rsx::Node> for AttributeToken {
@@ -41,8 +139,7 @@ impl TryFrom<syn_rsx::Node> for AttributeToken {
                 let keys = key.split(':').collect::<Vec<_>>();
                 Ok(AttributeToken::from_keys_expr_pair(&keys, expr))
             } else {
-                let name = under_to_dash(&key);
-                Ok(AttributeToken::BooleanTrue(name))
+                Ok(AttributeToken::from_key(&key))
             }
         } else {
             Err(Error::new(span, "dom attribute is missing a name"))
@@ -52,8 +149,21 @@ impl TryFrom<syn_rsx::Node> for AttributeToken {
 
 impl Parse for AttributeToken {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        #[cfg(feature = "validate-attributes")]
+        let span = input.span();
+        if input.parse::<Option<Token![..]>>()?.is_some() {
+            return Ok(AttributeToken::Spread(input.parse::<Expr>()?));
+        }
         let mut keys: Vec<String> = vec![];
-        while !input.lookahead1().peek(Token![=]) && !input.is_empty() {
+        let mut payload: Option<Ident> = None;
+        while !input.lookahead1().peek(Token![=]) && !input.peek(Token![,]) && !input.is_empty() {
+            if input.peek(token::Paren) {
+                // a typed event payload, eg. `on:input(value)`
+                let content;
+                let _ = syn::parenthesized!(content in input);
+                payload = Some(content.parse::<Ident>()?);
+                break;
+            }
             let key_segment = match input.parse::<Ident>() {
                 Ok(ident) => Ok(format!("{}", ident)),
                 Err(e1) => {
@@ -67,17 +177,50 @@ impl Parse for AttributeToken {
             let _ = input.parse::<Option<Token![:]>>()?;
             keys.push(key_segment);
         }
-        if input.parse::<Token![=]>().is_ok() {
+        #[cfg(feature = "validate-attributes")]
+        if let [name] = keys.as_slice() {
+            let name = under_to_dash(name);
+            if !crate::attributes::is_known_attribute(&name) {
+                return Err(Error::new(
+                    span,
+                    format!(
+                        "unknown attribute `{}`, use `attr:{}` if this is intended",
+                        name, name
+                    ),
+                ));
+            }
+        }
+        if let Some(payload) = payload {
+            input.parse::<Token![=]>()?;
+            let expr = input.parse::<Expr>()?;
+            match (keys.as_slice(), payload.to_string().as_str()) {
+                ([on, event], "value") if on == "on" => {
+                    Ok(AttributeToken::OnValue(event.clone(), expr))
+                }
+                _ => Err(Error::new(
+                    payload.span(),
+                    "unsupported event payload, expected `on:<event>(value)`",
+                )),
+            }
+        } else if input.parse::<Token![=]>().is_ok() {
             let expr = input.parse::<Expr>()?;
             Ok(AttributeToken::from_keys_expr_pair(&keys, expr))
         } else {
-            let key = under_to_dash(keys.join(":"));
-            Ok(AttributeToken::BooleanTrue(key))
+            Ok(AttributeToken::from_key(&keys.join(":")))
         }
     }
 }
 
 impl AttributeToken {
+    /// Create an attribute token from a key given without a value.
+    pub fn from_key(key: &str) -> Self {
+        if key == "xmlns:svg" {
+            AttributeToken::Namespace(syn::parse_quote!(#SVG_NAMESPACE))
+        } else {
+            AttributeToken::BooleanTrue(under_to_dash(key))
+        }
+    }
+
     pub fn from_keys_expr_pair(keys: &[impl AsRef<str>], expr: Expr) -> Self {
         let ks = keys.iter().map(|s| s.as_ref()).collect::<Vec<_>>();
         match ks.as_slice() {
@@ -85,19 +228,33 @@ impl AttributeToken {
             ["capture", "view"] => AttributeToken::CaptureView(expr),
             ["capture", "for_each"] => AttributeToken::CaptureForEach(expr),
             ["xmlns"] => AttributeToken::Xmlns(expr),
+            ["namespace"] => AttributeToken::Namespace(expr),
             ["style"] => AttributeToken::Style(expr),
             ["style", name] => {
                 let name = under_to_dash(name);
                 AttributeToken::StyleSingle(name, expr)
             }
             ["on", event] => AttributeToken::On(event.to_string(), expr),
+            ["bind", property] => AttributeToken::Bind(property.to_string(), expr),
             ["window", event] => AttributeToken::Window(event.to_string(), expr),
             ["document", event] => AttributeToken::Document(event.to_string(), expr),
             ["boolean", name] => {
                 let name = under_to_dash(name);
                 AttributeToken::BooleanSingle(name, expr)
             }
+            ["class", name] => {
+                let name = under_to_dash(name);
+                AttributeToken::ClassToggle(name, expr)
+            }
             ["patch", "children"] => AttributeToken::PatchChildren(expr),
+            ["patch", "children", "reversed"] => AttributeToken::PatchChildrenReversed(expr),
+            ["test", "id"] => AttributeToken::TestId(expr),
+            // anything but an integer literal is still set as a plain attribute
+            ["tabindex"] if is_int_lit(&expr) => AttributeToken::TabIndex(expr),
+            ["attr", name] => {
+                let name = under_to_dash(name);
+                AttributeToken::Attrib(name, expr)
+            }
             [attribute_name] => {
                 let name = under_to_dash(attribute_name);
                 AttributeToken::Attrib(name, expr)
@@ -123,16 +280,27 @@ impl AttributeToken {
             CaptureForEach(expr) => Ok(quote! {
                 .with_capture_for_each(#expr)
             }),
-            Xmlns(_) => Ok(quote!{}),// handled by a preprocessor
+            Xmlns(_) => Ok(quote! {}),     // handled by a preprocessor
+            Namespace(_) => Ok(quote! {}), // handled by a preprocessor
             Style(expr) => Ok(quote! {
                 .with_style_stream(#expr)
             }),
             StyleSingle(name, expr) => Ok(quote! {
-                .with_single_style_stream(#name, #expr)
+                .with_single_style(#name, #expr)
             }),
             On(name, expr) => Ok(quote! {
                 .with_event(#name, "myself", #expr)
             }),
+            OnValue(name, expr) if cfg!(feature = "dom") => Ok(on_value_token_stream(name, expr)),
+            OnValue(name, _) => Err(Error::new(
+                Span::call_site(),
+                format!("`on:{}(value)` is only supported by mogwai-dom", name),
+            )),
+            Bind(property, _) if cfg!(feature = "dom") => bind_token_stream(property),
+            Bind(property, _) => Err(Error::new(
+                Span::call_site(),
+                format!("`bind:{}` is only supported by mogwai-dom", property),
+            )),
             Window(name, expr) => Ok(quote! {
                 .with_event(#name, "window", #expr)
             }),
@@ -145,12 +313,27 @@ impl AttributeToken {
             PatchChildren(expr) => Ok(quote! {
                 .with_child_stream(#expr)
             }),
+            PatchChildrenReversed(expr) => Ok(quote! {
+                .with_reversed_child_stream(#expr)
+            }),
+            TestId(expr) => Ok(quote! {
+                .with_test_id(#expr)
+            }),
+            TabIndex(expr) => Ok(quote! {
+                .with_tabindex(#expr)
+            }),
             Attrib(name, expr) => Ok(quote! {
                 .with_single_attrib_stream(#name, #expr)
             }),
             BooleanTrue(expr) => Ok(quote! {
                 .with_single_bool_attrib_stream(#expr, true)
             }),
+            ClassToggle(name, expr) => Ok(quote! {
+                .with_class_toggle(#name, #expr)
+            }),
+            Spread(expr) => Ok(quote! {
+                .with_attribs(#expr)
+            }),
         }
     }
 }
@@ -166,13 +349,81 @@ pub enum ViewToken {
     },
     Text(syn::Expr),
     Block(syn::Expr),
+    /// `{if cond { node } else { node }}`, which becomes an `Option` child.
+    If {
+        condition: syn::Expr,
+        then_branch: Box<ViewToken>,
+        /// Either another `If` (for `else if`) or the `else` node.
+        else_branch: Option<Box<ViewToken>>,
+    },
+}
+
+impl ViewToken {
+    /// Parse the rest of an `if` node, starting at the `if` keyword.
+    fn parse_if(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let _if: Token![if] = input.parse()?;
+        let condition = Expr::parse_without_eager_brace(input)?;
+        let then_branch = Box::new(ViewToken::parse_branch(input)?);
+        let else_branch = if input.peek(Token![else]) {
+            let _else: Token![else] = input.parse()?;
+            if input.peek(Token![if]) {
+                Some(Box::new(ViewToken::parse_if(input)?))
+            } else {
+                Some(Box::new(ViewToken::parse_branch(input)?))
+            }
+        } else {
+            None
+        };
+        Ok(ViewToken::If {
+            condition,
+            then_branch,
+            else_branch,
+        })
+    }
+
+    /// Parse a braced `if` node, eg. `{if cond { node }}`.
+    fn parse_braced_if(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let content;
+        let _brace: token::Brace = syn::braced!(content in input);
+        if !content.peek(Token![if]) {
+            return Err(content.error("expected an `if` node"));
+        }
+        let token = ViewToken::parse_if(&content)?;
+        if !content.is_empty() {
+            return Err(content.error("unexpected tokens after `if` node"));
+        }
+        Ok(token)
+    }
+
+    /// Parse one braced branch of an `if` node, which must hold exactly one
+    /// node.
+    fn parse_branch(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let content;
+        let brace: token::Brace = syn::braced!(content in input);
+        let mut branch: ViewTokens = content.parse()?;
+        if branch.views.len() != 1 {
+            return Err(Error::new(
+                brace.span,
+                "each branch of an `if` node must contain exactly one node",
+            ));
+        }
+        Ok(branch.views.remove(0))
+    }
 }
 
 impl Parse for ViewToken {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
         let lookahead = input.lookahead1();
         if lookahead.peek(token::Brace) {
-            Ok(ViewToken::Block(input.parse::<syn::Expr>()?))
+            // `{if ...}` is an `if` node only when every branch is exactly one
+            // node, otherwise it is a plain rust `if` expression
+            let fork = input.fork();
+            if let Ok(token) = ViewToken::parse_braced_if(&fork) {
+                input.advance_to(&fork);
+                Ok(token)
+            } else {
+                Ok(ViewToken::Block(input.parse::<syn::Expr>()?))
+            }
         } else if lookahead.peek(LitStr) {
             Ok(ViewToken::Text(input.parse::<syn::Expr>()?))
         } else {
@@ -27,6 +27,139 @@ pub mod mpsc {
             }
         }
     }
+
+    /// The error returned by [`SenderExt::send_timeout`], which gives back
+    /// the value that could not be sent.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum SendTimeoutError<T> {
+        /// The channel stayed full until the timeout.
+        Timeout(T),
+        /// The channel is closed.
+        Closed(T),
+    }
+
+    impl<T> SendTimeoutError<T> {
+        /// Return the value that could not be sent, so it can be retried.
+        pub fn into_inner(self) -> T {
+            match self {
+                SendTimeoutError::Timeout(t) | SendTimeoutError::Closed(t) => t,
+            }
+        }
+    }
+
+    impl<T> std::fmt::Display for SendTimeoutError<T> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                SendTimeoutError::Timeout(_) => {
+                    f.write_str("timed out sending into a full channel")
+                }
+                SendTimeoutError::Closed(_) => f.write_str("sending into a closed channel"),
+            }
+        }
+    }
+
+    impl<T: std::fmt::Debug> std::error::Error for SendTimeoutError<T> {}
+
+    /// Extensions for mpsc [`Sender`]s.
+    pub trait SenderExt<T> {
+        /// Send a value, waiting at most `millis` milliseconds for room in the
+        /// channel.
+        ///
+        /// If the value can't be sent in time, or the channel is closed, the
+        /// value is given back in the error so it can be retried. While the
+        /// channel is full the value is offered to it again every millisecond.
+        fn send_timeout(
+            &self,
+            t: T,
+            millis: u64,
+        ) -> std::pin::Pin<Box<dyn Future<Output = Result<(), SendTimeoutError<T>>> + Send + '_>>;
+    }
+
+    impl<T: Send + 'static> SenderExt<T> for Sender<T> {
+        fn send_timeout(
+            &self,
+            t: T,
+            millis: u64,
+        ) -> std::pin::Pin<Box<dyn Future<Output = Result<(), SendTimeoutError<T>>> + Send + '_>>
+        {
+            Box::pin(async move {
+                // async_channel's send future can't give its value back when it
+                // loses the race, so the value is held here between tries
+                let mut value = Some(t);
+                let send = async {
+                    loop {
+                        // UNWRAP: the value is put back whenever the send waits
+                        match async_channel::Sender::try_send(self, value.take().unwrap()) {
+                            Ok(()) => return Ok(()),
+                            Err(async_channel::TrySendError::Full(t)) => {
+                                value = Some(t);
+                                let _ = crate::time::wait_millis(1).await;
+                            }
+                            Err(async_channel::TrySendError::Closed(t)) => {
+                                return Err(SendTimeoutError::Closed(t))
+                            }
+                        }
+                    }
+                };
+                let sent = futures_lite::future::or(async { Some(send.await) }, async {
+                    let _ = crate::time::wait_millis(millis).await;
+                    None
+                })
+                .await;
+                match sent {
+                    Some(result) => result,
+                    // UNWRAP: the send only stops early while waiting, when it
+                    // holds the value
+                    None => Err(SendTimeoutError::Timeout(value.take().unwrap())),
+                }
+            })
+        }
+    }
+
+    #[cfg(all(test, not(target_arch = "wasm32")))]
+    mod test {
+        use super::*;
+
+        #[test]
+        fn send_timeout_gives_back_the_value() {
+            futures_lite::future::block_on(async {
+                let (tx, rx) = bounded::<u32>(1);
+                assert_eq!(tx.send_timeout(1, 10).await, Ok(()));
+                let err = tx.send_timeout(2, 10).await.unwrap_err();
+                assert_eq!(err, SendTimeoutError::Timeout(2));
+                assert_eq!(err.into_inner(), 2);
+
+                assert_eq!(rx.recv().await, Ok(1));
+                assert_eq!(tx.send_timeout(3, 10).await, Ok(()));
+
+                // a waiting send goes through as soon as there is room
+                let (sent, received) = futures_lite::future::zip(tx.send_timeout(5, 1000), async {
+                    let _ = crate::time::wait_millis(5).await;
+                    rx.recv().await
+                })
+                .await;
+                assert_eq!(sent, Ok(()));
+                assert_eq!(received, Ok(3));
+                assert_eq!(rx.recv().await, Ok(5));
+
+                drop(rx);
+                assert_eq!(
+                    tx.send_timeout(4, 10).await,
+                    Err(SendTimeoutError::Closed(4))
+                );
+
+                // values don't need to be cloned to be given back
+                #[derive(Debug, PartialEq)]
+                struct Unique(u32);
+                let (tx, _rx) = bounded::<Unique>(1);
+                assert_eq!(tx.send_timeout(Unique(1), 10).await, Ok(()));
+                assert_eq!(
+                    tx.send_timeout(Unique(2), 10).await,
+                    Err(SendTimeoutError::Timeout(Unique(2)))
+                );
+            });
+        }
+    }
 }
 
 pub mod broadcast {
@@ -96,6 +229,33 @@ pub mod broadcast {
                 .map_err(|_| SendError::Closed)
         }
 
+        /// Broadcast a message to all linked [`Receiver`]s without waiting,
+        /// overwriting the oldest queued message if the channel is full.
+        ///
+        /// This gives "latest wins" semantics: a receiver that falls behind
+        /// skips the evicted messages and only sees the most recent
+        /// `capacity` of them. Returns whether a message was evicted.
+        ///
+        /// The oldest message is evicted by the channel's overflow mode, which
+        /// this turns on and leaves on. From then on every send into the full
+        /// channel, through any sender, evicts the oldest message instead of
+        /// waiting for room.
+        ///
+        /// ## Errors
+        /// Returns the message if the channel is closed, or if it has no
+        /// active receivers.
+        pub fn send_overwrite(&self, item: T) -> Result<bool, OverwriteError<T>> {
+            let mut inner = self.inner.clone();
+            inner.set_overflow(true);
+            match inner.try_broadcast(item) {
+                Ok(evicted) => Ok(evicted.is_some()),
+                // only possible if another sender turned overflow mode off again
+                Err(async_broadcast::TrySendError::Full(t)) => Err(OverwriteError::Full(t)),
+                Err(async_broadcast::TrySendError::Closed(t)) => Err(OverwriteError::Closed(t)),
+                Err(async_broadcast::TrySendError::Inactive(t)) => Err(OverwriteError::Inactive(t)),
+            }
+        }
+
         /// Waits until the channel of the given `Sender` is empty.
         pub async fn until_empty(&self) {
             while !self.inner.is_empty() {
@@ -104,6 +264,44 @@ pub mod broadcast {
         }
     }
 
+    /// The error returned by [`Sender::send_overwrite`], which gives back
+    /// the message that could not be sent.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum OverwriteError<T> {
+        /// The channel is full and another sender turned off its overflow
+        /// mode.
+        Full(T),
+        /// The channel is closed.
+        Closed(T),
+        /// The channel has no active receivers.
+        Inactive(T),
+    }
+
+    impl<T> OverwriteError<T> {
+        /// Return the message that could not be sent, so it can be retried.
+        pub fn into_inner(self) -> T {
+            match self {
+                OverwriteError::Full(t)
+                | OverwriteError::Closed(t)
+                | OverwriteError::Inactive(t) => t,
+            }
+        }
+    }
+
+    impl<T> std::fmt::Display for OverwriteError<T> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                OverwriteError::Full(_) => f.write_str("sending into a full channel"),
+                OverwriteError::Closed(_) => f.write_str("sending into a closed channel"),
+                OverwriteError::Inactive(_) => {
+                    f.write_str("sending into a channel without active receivers")
+                }
+            }
+        }
+    }
+
+    impl<T: std::fmt::Debug> std::error::Error for OverwriteError<T> {}
+
     /// An asynchronous broadcast `Receiver`.
     ///
     /// This is a thin wrapper around [`async_broadcast::Receiver`].
@@ -120,12 +318,65 @@ pub mod broadcast {
                 async_broadcast::RecvError::Closed => SendError::Full,
             })
         }
+
+        /// Receive the most recent item in the channel, discarding any older
+        /// items that are still buffered.
+        ///
+        /// This is useful when a slow consumer only cares about the newest
+        /// value, eg. to skip rendering stale frames. If the channel is empty
+        /// this waits for the next item. Returns `None` once the channel is
+        /// closed and empty.
+        pub async fn latest(&mut self) -> Option<T> {
+            let mut latest = None;
+            loop {
+                match self.inner.try_recv() {
+                    Ok(t) => latest = Some(t),
+                    Err(async_broadcast::TryRecvError::Overflowed(_)) => {}
+                    Err(_) => break,
+                }
+            }
+            if latest.is_some() {
+                return latest;
+            }
+            loop {
+                match self.inner.recv().await {
+                    Ok(t) => return Some(t),
+                    Err(async_broadcast::RecvError::Overflowed(_)) => {}
+                    Err(async_broadcast::RecvError::Closed) => return None,
+                }
+            }
+        }
+    }
+
+    /// The error returned by [`try_bounded`] when asked for a channel with no
+    /// capacity.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct CapacityError;
+
+    impl std::fmt::Display for CapacityError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str("broadcast channel capacity must be greater than zero")
+        }
     }
 
+    impl std::error::Error for CapacityError {}
+
     /// Create an asynchronous multi-producer, multi-consumer broadcast channel.
+    ///
+    /// ## Panics
+    /// Panics if `cap` is zero. Use [`try_bounded`] to handle that case.
     pub fn bounded<T: Clone>(cap: usize) -> (Sender<T>, Receiver<T>) {
+        try_bounded(cap).expect("could not create broadcast channel")
+    }
+
+    /// Create an asynchronous multi-producer, multi-consumer broadcast channel,
+    /// or return an error if `cap` is zero.
+    pub fn try_bounded<T: Clone>(cap: usize) -> Result<(Sender<T>, Receiver<T>), CapacityError> {
+        if cap == 0 {
+            return Err(CapacityError);
+        }
         let (tx, rx) = async_broadcast::broadcast::<T>(cap);
-        (Sender { inner: tx }, Receiver { inner: rx })
+        Ok((Sender { inner: tx }, Receiver { inner: rx }))
     }
 
     impl<T: Clone> Stream for Receiver<T> {
@@ -191,6 +442,53 @@ pub mod broadcast {
                 let _ = rx.next().await.unwrap();
             })
         }
+
+        #[test]
+        fn send_overwrite_keeps_the_latest() {
+            futures_lite::future::block_on(async {
+                let (tx, rx) = bounded::<u32>(2);
+                assert!(!tx.send_overwrite(1).unwrap());
+                assert!(!tx.send_overwrite(2).unwrap());
+                assert!(tx.send_overwrite(3).unwrap());
+                assert!(tx.send_overwrite(4).unwrap());
+                // the channel is left in overflow mode, with its capacity intact
+                assert_eq!(tx.inner.capacity(), 2);
+                assert!(tx.inner.overflow());
+                tx.try_send(5).unwrap();
+                let inactive = rx.inner.clone().deactivate();
+                assert_eq!(rx.take(2).collect::<Vec<_>>().await, vec![4, 5]);
+                // with no active receivers the message is returned, not dropped
+                let err = tx.send_overwrite(6).unwrap_err();
+                assert_eq!(err, OverwriteError::Inactive(6));
+                assert_eq!(err.into_inner(), 6);
+                drop(inactive);
+            })
+        }
+
+        #[test]
+        fn latest_skips_stale_items() {
+            futures_lite::future::block_on(async {
+                let (tx, mut rx) = bounded::<u32>(4);
+                for n in 1..=4 {
+                    tx.broadcast(n).await.unwrap();
+                }
+                assert_eq!(rx.latest().await, Some(4));
+                assert!(rx.inner.is_empty());
+
+                tx.broadcast(5).await.unwrap();
+                assert_eq!(rx.latest().await, Some(5));
+                drop(tx);
+                assert_eq!(rx.latest().await, None);
+            })
+        }
+
+        #[test]
+        fn try_bounded_rejects_zero_capacity() {
+            assert_eq!(try_bounded::<u32>(0).err(), Some(CapacityError));
+            let err = anyhow::Error::from(try_bounded::<u32>(0).unwrap_err());
+            assert!(err.to_string().contains("greater than zero"));
+            assert!(try_bounded::<u32>(1).is_ok());
+        }
     }
 }
 
@@ -1,12 +1,18 @@
 //! Re-exports of the [futures_lite] crate, along with extensions and helper types.
 use std::{
-    sync::{Arc, Mutex, RwLock},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, RwLock,
+    },
     task::Waker,
 };
 
 pub use futures_lite::future::*;
 
-use crate::sink::{SendError, Sink, TrySendError};
+use crate::{
+    sink::{SendError, Sink, TrySendError},
+    stream::Stream,
+};
 
 /// A future view, which uses `Sink` to store the result of an
 /// operation.
@@ -88,6 +94,9 @@ impl<T: Send + Sync + Clone> Captured<T> {
     }
 
     /// Gives the current value syncronously, if possible.
+    ///
+    /// This does not consume the capture, so a later [`Captured::get`] still
+    /// resolves. It is useful in synchronous contexts like `post:build` ops.
     pub fn current(&self) -> Option<T> {
         let lock = self.inner.read().ok()?;
         lock.as_ref().cloned()
@@ -101,6 +110,212 @@ impl<T: Send + Sync + Clone> Captured<T> {
     }
 }
 
+/// A list of views, which uses `Sink` to store the results of many
+/// operations.
+///
+/// Like [`Captured`], but every `T` sent in is kept, in the order it was
+/// sent. This is useful for capturing each child of a dynamic list with
+/// `capture:view = list.sink()`.
+pub struct CapturedList<T> {
+    wakers: Arc<Mutex<Vec<Waker>>>,
+    inner: Arc<RwLock<Vec<T>>>,
+}
+
+impl<T> Clone for CapturedList<T> {
+    fn clone(&self) -> Self {
+        Self {
+            wakers: self.wakers.clone(),
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> Default for CapturedList<T> {
+    fn default() -> Self {
+        Self {
+            wakers: Arc::new(Mutex::new(vec![])),
+            inner: Arc::new(RwLock::new(vec![])),
+        }
+    }
+}
+
+impl<T: Send + Sync + Clone> Sink<T> for CapturedList<T> {
+    fn send(
+        &self,
+        item: T,
+    ) -> std::pin::Pin<Box<dyn Future<Output = Result<(), SendError>> + Send + '_>> {
+        Box::pin(std::future::ready(
+            self.try_send(item).map_err(|_| SendError::Full),
+        ))
+    }
+
+    fn try_send(&self, item: T) -> Result<(), TrySendError> {
+        // UNWRAP: if we can't get this write, we want the program to panic
+        self.inner.write().unwrap().push(item);
+        // UNWRAP: if we can't get this lock, we want the program to panic
+        for waker in self.wakers.lock().unwrap().drain(..) {
+            waker.wake();
+        }
+        Ok(())
+    }
+}
+
+/// Resolves to the item at `index` of a [`CapturedList`] once it is captured.
+struct CapturedAt<T> {
+    list: CapturedList<T>,
+    index: usize,
+}
+
+impl<T: Clone> Future for CapturedAt<T> {
+    type Output = T;
+
+    fn poll(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Self::Output> {
+        // hold the read lock while registering so a send can't slip in between
+        let read = self.list.inner.read().unwrap();
+        if let Some(t) = read.get(self.index) {
+            std::task::Poll::Ready(t.clone())
+        } else {
+            self.list.wakers.lock().unwrap().push(cx.waker().clone());
+            std::task::Poll::Pending
+        }
+    }
+}
+
+impl<T: Send + Sync + Clone> CapturedList<T> {
+    /// Return a sink.
+    pub fn sink(&self) -> impl Sink<T> {
+        self.clone()
+    }
+
+    /// Gives clones of all the items captured so far, syncronously.
+    pub fn current(&self) -> Vec<T> {
+        self.inner
+            .read()
+            .map(|lock| lock.clone())
+            .unwrap_or_default()
+    }
+
+    /// The number of items captured so far.
+    pub fn len(&self) -> usize {
+        self.inner.read().map(|lock| lock.len()).unwrap_or_default()
+    }
+
+    /// Whether nothing has been captured yet.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Await the first capture and return clones of all the items captured
+    /// so far, in the order they were captured.
+    ///
+    /// Views are captured as they are built, so once a list of children has
+    /// been built this contains every child.
+    pub async fn get_all(&self) -> Vec<T> {
+        let _ = CapturedAt {
+            list: self.clone(),
+            index: 0,
+        }
+        .await;
+        self.current()
+    }
+
+    /// A stream of clones of every captured item, in the order they were
+    /// captured, including those captured before the stream was created.
+    ///
+    /// The stream waits for more captures and never ends.
+    pub fn stream(&self) -> impl Stream<Item = T> + Send + Unpin + 'static
+    where
+        T: 'static,
+    {
+        Box::pin(futures_lite::stream::unfold(
+            (self.clone(), 0),
+            |(list, index)| async move {
+                let t = CapturedAt {
+                    list: list.clone(),
+                    index,
+                }
+                .await;
+                Some((t, (list, index + 1)))
+            },
+        ))
+    }
+}
+
+/// A handle to a task added with
+/// [`ViewBuilder::with_task_handle`](crate::view::ViewBuilder::with_task_handle).
+///
+/// The task is cancelled when [`TaskHandle::abort`] is called or when the
+/// handle is dropped.
+#[derive(Debug)]
+pub struct TaskHandle {
+    aborted: Arc<AtomicBool>,
+    waker: Arc<Mutex<Option<Waker>>>,
+}
+
+impl Drop for TaskHandle {
+    fn drop(&mut self) {
+        self.abort();
+    }
+}
+
+impl TaskHandle {
+    /// Wrap `fut` in a future that completes early once the returned handle
+    /// is aborted or dropped.
+    pub fn new<Fut: Future<Output = ()>>(fut: Fut) -> (Self, impl Future<Output = ()>) {
+        let handle = TaskHandle {
+            aborted: Default::default(),
+            waker: Default::default(),
+        };
+        let abortable = Abortable {
+            fut: Box::pin(fut),
+            aborted: handle.aborted.clone(),
+            waker: handle.waker.clone(),
+        };
+        (handle, abortable)
+    }
+
+    /// Cancel the task. The task's future is dropped the next time it would
+    /// be polled.
+    pub fn abort(&self) {
+        self.aborted.store(true, Ordering::SeqCst);
+        // UNWRAP: if we can't get this lock, we want the program to panic
+        if let Some(waker) = self.waker.lock().unwrap().take() {
+            waker.wake();
+        }
+    }
+
+    /// Whether the task has been cancelled.
+    pub fn is_aborted(&self) -> bool {
+        self.aborted.load(Ordering::SeqCst)
+    }
+}
+
+struct Abortable<Fut> {
+    fut: std::pin::Pin<Box<Fut>>,
+    aborted: Arc<AtomicBool>,
+    waker: Arc<Mutex<Option<Waker>>>,
+}
+
+impl<Fut: Future<Output = ()>> Future for Abortable<Fut> {
+    type Output = ();
+
+    fn poll(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Self::Output> {
+        let abortable = self.get_mut();
+        if abortable.aborted.load(Ordering::SeqCst) {
+            return std::task::Poll::Ready(());
+        }
+        // UNWRAP: if we can't get this lock, we want the program to panic
+        *abortable.waker.lock().unwrap() = Some(cx.waker().clone());
+        abortable.fut.as_mut().poll(cx)
+    }
+}
+
 pub struct JoinAll<Fut: Future>(Vec<(usize, Fut)>, Vec<Option<Fut::Output>>);
 
 pub fn join_all<Fut: Future + Unpin>(futures: impl IntoIterator<Item = Fut>) -> JoinAll<Fut>
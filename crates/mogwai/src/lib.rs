@@ -49,13 +49,14 @@ pub mod stream;
 pub mod model;
 pub mod patch;
 pub mod relay;
+pub mod state_machine;
 pub mod time;
 pub mod view;
 pub use mogwai_macros::{builder, html, rsx};
 
 pub mod prelude {
     //! Re-exports for convenience
-    pub use super::future::Captured;
+    pub use super::future::{Captured, CapturedList, TaskHandle};
     pub use super::sink::{SendError, Sink, SinkExt, TrySendError};
     pub use super::stream::{Stream, StreamExt};
     pub use super::patch::{HashPatch, HashPatchApply, ListPatch, ListPatchApply};
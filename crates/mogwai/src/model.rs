@@ -2,13 +2,18 @@
 use std::{
     collections::HashMap,
     ops::{DerefMut, RangeBounds},
+    future::Future,
+    pin::Pin,
     sync::Arc,
 };
 
 use anyhow::Context;
 use async_broadcast::{broadcast, Receiver, Sender};
 use async_lock::{RwLock, RwLockReadGuard, RwLockUpgradableReadGuard};
-use futures_lite::stream::{Stream, StreamExt};
+use futures_lite::{
+    stream::{Stream, StreamExt},
+    FutureExt,
+};
 
 pub use crate::patch::{HashPatchApply, ListPatchApply};
 use crate::{
@@ -23,7 +28,8 @@ use crate::{
 ///
 /// ## Warning
 /// If [`Model::visit_mut`] is called in quick succession, only the
-/// latest, unique values will be sent to downstream observers.
+/// latest, unique values will be sent to downstream observers. Use
+/// [`Model::subscribe`] to observe every value.
 ///
 /// ```rust
 /// use mogwai::{model::Model, prelude::*};
@@ -51,6 +57,8 @@ use crate::{
 pub struct Model<T> {
     value: Arc<RwLock<T>>,
     chan: (Sender<T>, Receiver<T>),
+    // one unbounded queue per subscriber, see `Model::subscribe`
+    subscribers: Arc<std::sync::Mutex<Vec<async_channel::Sender<T>>>>,
 }
 
 impl<T> std::fmt::Debug for Model<T> {
@@ -65,6 +73,7 @@ impl<T> Clone for Model<T> {
         Model {
             value: self.value.clone(),
             chan: self.chan.clone(),
+            subscribers: self.subscribers.clone(),
         }
     }
 }
@@ -80,6 +89,15 @@ impl<T: Clone + PartialEq + Send + Sync> Model<T> {
         Model {
             value: Arc::new(RwLock::new(t)),
             chan: (tx, rx),
+            subscribers: Default::default(),
+        }
+    }
+
+    /// Queue a clone of `t` for every subscriber, forgetting those that
+    /// have been dropped.
+    fn notify_subscribers(&self, t: &T) {
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.retain(|tx| tx.try_send(t.clone()).is_ok());
         }
     }
 
@@ -110,6 +128,7 @@ impl<T: Clone + PartialEq + Send + Sync> Model<T> {
         let mut lock = self.value.write().await;
         let x = f(lock.deref_mut());
         let t = lock.clone();
+        self.notify_subscribers(&t);
         self.chan.0.broadcast(t).await.unwrap();
         x
     }
@@ -134,6 +153,7 @@ impl<T: Clone + PartialEq + Send + Sync> Model<T> {
         let mut lock = self.value.try_write()?;
         let x = f(lock.deref_mut());
         let t = lock.clone();
+        self.notify_subscribers(&t);
         self.chan.0.try_broadcast(t).ok()?;
         Some(x)
     }
@@ -143,6 +163,25 @@ impl<T: Clone + PartialEq + Send + Sync> Model<T> {
         self.visit_mut(|v| std::mem::replace(v, t.into())).await
     }
 
+    /// Set the value of the model, but only if it differs from the current
+    /// value.
+    ///
+    /// Downstream observers are only sent an update when the value actually
+    /// changes, which avoids re-rendering views that would not change.
+    /// Returns whether the value was changed.
+    pub async fn set_if_changed(&self, t: impl Into<T>) -> bool {
+        let t = t.into();
+        let lock = self.value.upgradable_read().await;
+        if *lock == t {
+            return false;
+        }
+        let mut lock = RwLockUpgradableReadGuard::upgrade(lock).await;
+        *lock = t.clone();
+        self.notify_subscribers(&t);
+        self.chan.0.broadcast(t).await.unwrap();
+        true
+    }
+
     /// Produce a stream of updated values.
     ///
     /// You should not rely on receiving every update, only the most recent.
@@ -153,6 +192,20 @@ impl<T: Clone + PartialEq + Send + Sync> Model<T> {
         self.chan.1.clone()
     }
 
+    /// Produce a stream of every value the model is set to from now on.
+    ///
+    /// Unlike [`Model::stream`] no update is clobbered: each change made with
+    /// [`Model::visit_mut`], [`Model::replace`] and the like is queued for the
+    /// subscriber until it is read. The stream ends once the model and all its
+    /// clones have been dropped.
+    pub fn subscribe(&self) -> impl Stream<Item = T> + Send + Sync {
+        let (tx, rx) = async_channel::unbounded();
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.push(tx);
+        }
+        rx
+    }
+
     /// Map this model into a struct that can be used as a `MogwaiValue`.
     ///
     /// This is useful for using projections of a `Model` as a parameter to
@@ -177,6 +230,94 @@ impl<T: Clone + PartialEq + Send + Sync> Model<T> {
     pub fn try_refresh(&self) -> Option<()> {
         self.try_visit_mut(|_| ())
     }
+
+    /// Derive a new model from the result of an async computation on this
+    /// model's value.
+    ///
+    /// Returns the derived model, which starts as `None`, and a future that
+    /// drives the derivation. Each time this model's value changes the future
+    /// starts computing `f` for the new value, dropping (and thereby cancelling)
+    /// any computation still in flight. When a computation resolves, the derived
+    /// model is updated with its result.
+    ///
+    /// The returned future must be run for the derived model to update, eg.
+    /// with [`ViewBuilder::with_task`]. It completes once this model and all
+    /// its clones have been dropped and the last computation has resolved.
+    pub fn derive_async<U, F, Fut>(
+        &self,
+        f: F,
+    ) -> (Model<Option<U>>, impl Future<Output = ()> + Send + 'static)
+    where
+        T: 'static,
+        U: Clone + PartialEq + Send + Sync + 'static,
+        F: Fn(&T) -> Fut + Send + 'static,
+        Fut: Future<Output = U> + Send + 'static,
+    {
+        enum Step<T, U> {
+            Changed(Option<T>),
+            Resolved(U),
+        }
+
+        let derived = Model::new(None);
+        let model = derived.clone();
+        let mut updates = self.stream();
+        let task = async move {
+            let mut in_flight: Option<Pin<Box<dyn Future<Output = U> + Send>>> = None;
+            loop {
+                let step = if let Some(fut) = in_flight.as_mut() {
+                    let changed = async { Step::Changed(updates.next().await) };
+                    let resolved = async { Step::Resolved(fut.await) };
+                    changed.or(resolved).await
+                } else {
+                    Step::Changed(updates.next().await)
+                };
+                match step {
+                    Step::Changed(Some(t)) => in_flight = Some(Box::pin(f(&t))),
+                    Step::Changed(None) => {
+                        if let Some(fut) = in_flight.take() {
+                            let u = fut.await;
+                            model.visit_mut(|v| *v = Some(u)).await;
+                        }
+                        break;
+                    }
+                    Step::Resolved(u) => {
+                        in_flight = None;
+                        model.visit_mut(|v| *v = Some(u)).await;
+                    }
+                }
+            }
+        };
+        (derived, task)
+    }
+}
+
+/// Optimistically set `model` to `optimistic_value` while `action` runs.
+///
+/// The model is updated immediately, so views reflect the change without
+/// waiting on `action`. If `action` fails the model's prior value is
+/// restored. Returns the result of `action`.
+///
+/// ```rust
+/// use mogwai::model::{optimistic, Model};
+///
+/// mogwai::future::block_on(async {
+///     let liked = Model::new(false);
+///     let result = optimistic(&liked, true, async { anyhow::bail!("offline") }).await;
+///     assert!(result.is_err());
+///     assert_eq!(liked.current(), Some(false));
+/// });
+/// ```
+pub async fn optimistic<T: Clone + PartialEq + Send + Sync>(
+    model: &Model<T>,
+    optimistic_value: T,
+    action: impl Future<Output = anyhow::Result<()>>,
+) -> anyhow::Result<()> {
+    let prior = model.replace(optimistic_value).await;
+    let result = action.await;
+    if result.is_err() {
+        model.replace(prior).await;
+    }
+    result
 }
 
 /// Struct used to support `Model::map`.
@@ -212,6 +353,127 @@ where
     }
 }
 
+/// Wraps a [`Model`], keeping a history of its values for undo and redo.
+///
+/// ```rust
+/// use mogwai::model::History;
+///
+/// mogwai::future::block_on(async {
+///     let history = History::<String>::new("draft");
+///     history.set("final").await;
+///     assert!(history.undo().await);
+///     assert_eq!(history.model().read().await.as_str(), "draft");
+///     assert!(history.redo().await);
+///     assert_eq!(history.model().read().await.as_str(), "final");
+/// });
+/// ```
+pub struct History<T> {
+    model: Model<T>,
+    stacks: Arc<RwLock<(Vec<T>, Vec<T>)>>,
+    can_undo: Model<bool>,
+    can_redo: Model<bool>,
+}
+
+impl<T> Clone for History<T> {
+    fn clone(&self) -> Self {
+        History {
+            model: self.model.clone(),
+            stacks: self.stacks.clone(),
+            can_undo: self.can_undo.clone(),
+            can_redo: self.can_redo.clone(),
+        }
+    }
+}
+
+impl<T: Clone + PartialEq + Send + Sync> History<T> {
+    /// Create a new History with an empty past and future.
+    pub fn new(t: impl Into<T>) -> History<T> {
+        History {
+            model: Model::new(t),
+            stacks: Default::default(),
+            can_undo: Model::new(false),
+            can_redo: Model::new(false),
+        }
+    }
+
+    /// The model holding the present value.
+    ///
+    /// Mutations made directly through the model are not recorded in the
+    /// history.
+    pub fn model(&self) -> &Model<T> {
+        &self.model
+    }
+
+    /// Produce a stream of updated values.
+    ///
+    /// See [`Model::stream`].
+    pub fn stream(&self) -> impl Stream<Item = T> + Send + Sync {
+        self.model.stream()
+    }
+
+    /// Set the present value, pushing the previous one onto the undo history.
+    ///
+    /// This clears the redo history.
+    pub async fn set(&self, t: impl Into<T>) {
+        let mut stacks = self.stacks.write().await;
+        let (undos, redos) = &mut *stacks;
+        undos.push(self.model.replace(t).await);
+        redos.clear();
+        self.update_capabilities(undos, redos).await;
+    }
+
+    /// Restore the previous value, pushing the present value onto the redo
+    /// history.
+    ///
+    /// Returns `false` if there was nothing to undo.
+    pub async fn undo(&self) -> bool {
+        let mut stacks = self.stacks.write().await;
+        let (undos, redos) = &mut *stacks;
+        let undone = if let Some(t) = undos.pop() {
+            redos.push(self.model.replace(t).await);
+            true
+        } else {
+            false
+        };
+        self.update_capabilities(undos, redos).await;
+        undone
+    }
+
+    /// Restore the most recently undone value, pushing the present value onto
+    /// the undo history.
+    ///
+    /// Returns `false` if there was nothing to redo.
+    pub async fn redo(&self) -> bool {
+        let mut stacks = self.stacks.write().await;
+        let (undos, redos) = &mut *stacks;
+        let redone = if let Some(t) = redos.pop() {
+            undos.push(self.model.replace(t).await);
+            true
+        } else {
+            false
+        };
+        self.update_capabilities(undos, redos).await;
+        redone
+    }
+
+    /// Produce a stream of whether there is anything to undo, eg. for
+    /// enabling an "undo" button.
+    pub fn can_undo(&self) -> impl Stream<Item = bool> + Send + Sync {
+        self.can_undo.stream()
+    }
+
+    /// Produce a stream of whether there is anything to redo, eg. for
+    /// enabling a "redo" button.
+    pub fn can_redo(&self) -> impl Stream<Item = bool> + Send + Sync {
+        self.can_redo.stream()
+    }
+
+    async fn update_capabilities(&self, undos: &[T], redos: &[T]) {
+        self.can_undo.replace(!undos.is_empty()).await;
+        self.can_redo.replace(!redos.is_empty()).await;
+    }
+}
+
 /// Provides a patchable list of `T` and a stream of patch updates.
 ///
 /// [`ListPatchModel`] is great for synchronizing two or more list structures -
@@ -508,6 +770,140 @@ mod test {
         });
     }
 
+    #[test]
+    fn model_subscribe_yields_every_set() {
+        let model = Model::<u32>::new(0u32);
+        let snapshots = model.subscribe();
+        futures_lite::future::block_on(async move {
+            for n in 1..=3u32 {
+                model.replace(n).await;
+            }
+            drop(model);
+
+            assert_eq!(snapshots.collect::<Vec<_>>().await, vec![1, 2, 3]);
+        });
+    }
+
+    #[test]
+    fn model_derive_async_resolves_latest() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let model = Model::new(0u32);
+        let resolutions = Arc::new(AtomicUsize::new(0));
+        let counter = resolutions.clone();
+        let (derived, task) = model.derive_async(move |n: &u32| {
+            let n = *n;
+            let counter = counter.clone();
+            async move {
+                crate::time::wait_millis(20).await;
+                counter.fetch_add(1, Ordering::SeqCst);
+                n * 10
+            }
+        });
+
+        futures_lite::future::block_on(async move {
+            let changes = async {
+                for n in 1..=3 {
+                    crate::time::wait_millis(1).await;
+                    model.visit_mut(|t| *t = n).await;
+                }
+                drop(model);
+            };
+            let ((), ()) = futures_lite::future::zip(task, changes).await;
+            assert_eq!(derived.current(), Some(Some(30)));
+            assert_eq!(resolutions.load(Ordering::SeqCst), 1);
+        });
+    }
+
+    #[test]
+    fn optimistic_keeps_value_on_success() {
+        let model = Model::new("draft".to_string());
+        let updates = model.stream();
+        futures_lite::future::block_on(async move {
+            let saved = optimistic(&model, "saved".to_string(), async {
+                crate::time::wait_millis(10).await;
+                Ok(())
+            });
+            let seen = async {
+                // the optimistic value is visible before the action resolves
+                assert_eq!(model.current(), Some("saved".to_string()));
+                futures_lite::future::pending::<anyhow::Result<()>>().await
+            };
+            saved.or(seen).await.unwrap();
+            assert_eq!(model.current(), Some("saved".to_string()));
+            drop(model);
+            assert_eq!(updates.collect::<Vec<_>>().await, vec!["saved".to_string()]);
+        });
+    }
+
+    #[test]
+    fn optimistic_rolls_back_on_failure() {
+        let model = Model::new(1u32);
+        futures_lite::future::block_on(async {
+            let result = optimistic(&model, 2u32, async {
+                crate::time::wait_millis(10).await;
+                anyhow::bail!("request failed")
+            })
+            .await;
+            assert_eq!(result.unwrap_err().to_string(), "request failed");
+            assert_eq!(model.current(), Some(1));
+        });
+    }
+
+    #[test]
+    fn history_undo_redo() {
+        let history = History::<u32>::new(0u32);
+        futures_lite::future::block_on(async move {
+            let current = || history.model().current().unwrap();
+            assert!(!history.undo().await);
+            assert!(!history.redo().await);
+
+            for n in 1..=3u32 {
+                history.set(n).await;
+            }
+            assert!(history.undo().await);
+            assert!(history.undo().await);
+            assert_eq!(current(), 1);
+            assert!(history.redo().await);
+            assert_eq!(current(), 2);
+
+            // setting a new value discards the redo history
+            history.set(10u32).await;
+            assert!(!history.redo().await);
+            assert_eq!(current(), 10);
+
+            for expected in [2, 1, 0] {
+                assert!(history.undo().await);
+                assert_eq!(current(), expected);
+            }
+            assert!(!history.undo().await);
+            assert_eq!(current(), 0);
+        });
+    }
+
+    #[test]
+    fn history_capability_streams() {
+        let history = History::<String>::new("a");
+        let mut can_undo = history.can_undo();
+        let mut can_redo = history.can_redo();
+        futures_lite::future::block_on(async move {
+            assert_eq!(can_undo.next().await, Some(false));
+            assert_eq!(can_redo.next().await, Some(false));
+
+            history.set("b").await;
+            assert_eq!(can_undo.next().await, Some(true));
+            assert_eq!(can_redo.next().await, Some(false));
+
+            history.undo().await;
+            assert_eq!(can_undo.next().await, Some(false));
+            assert_eq!(can_redo.next().await, Some(true));
+
+            history.redo().await;
+            assert_eq!(can_undo.next().await, Some(true));
+            assert_eq!(can_redo.next().await, Some(false));
+        });
+    }
+
     #[test]
     fn list_patch_model_sanity() {
         let mut model: ListPatchModel<String> = ListPatchModel::new();
@@ -1,8 +1,9 @@
 //! Updates to lists and hashmaps encoded as enums.
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap, VecDeque},
     hash::Hash,
     ops::{Bound, RangeBounds},
+    sync::Arc,
 };
 
 fn clone_bound<T: Copy>(bound: Bound<&T>) -> Bound<T> {
@@ -31,6 +32,28 @@ pub enum ListPatch<T> {
     /// Remove the last item.
     Pop,
 
+    /// Replace the item at `index` with `value`, leaving the surrounding
+    /// items untouched. Zero-indexed.
+    Replace {
+        /// The index of the item to replace.
+        index: usize,
+        /// The new item.
+        value: T,
+    },
+
+    /// Move the item at index `from` to index `to`, without recreating it.
+    ///
+    /// `to` is the item's index after the move. Zero-indexed.
+    Move {
+        /// The current index of the item.
+        from: usize,
+        /// The index of the item after the move.
+        to: usize,
+    },
+
+    /// Apply each of the patches in order, as a single unit.
+    Batch(Vec<ListPatch<T>>),
+
     /// Do nothing.
     Noop,
 }
@@ -47,6 +70,17 @@ impl<T> ListPatch<T> {
             },
             ListPatch::Push(t) => ListPatch::Push(&t),
             ListPatch::Pop => ListPatch::Pop,
+            ListPatch::Replace { index, value } => ListPatch::Replace {
+                index: *index,
+                value,
+            },
+            ListPatch::Move { from, to } => ListPatch::Move {
+                from: *from,
+                to: *to,
+            },
+            ListPatch::Batch(patches) => {
+                ListPatch::Batch(patches.iter().map(ListPatch::as_ref).collect())
+            }
             ListPatch::Noop => ListPatch::Noop,
         }
     }
@@ -74,7 +108,18 @@ impl<T> ListPatch<T> {
 
     /// Construct a ListPatch that replaces the given index with the given item.
     pub fn replace(index: usize, item: T) -> Self {
-        Self::splice(index..=index, std::iter::once(item))
+        ListPatch::Replace { index, value: item }
+    }
+
+    /// Construct a ListPatch that moves the item at index `from` to index `to`.
+    pub fn move_item(from: usize, to: usize) -> Self {
+        ListPatch::Move { from, to }
+    }
+
+    /// Construct a ListPatch that applies the given patches in order, as a
+    /// single unit.
+    pub fn batch(patches: impl IntoIterator<Item = ListPatch<T>>) -> Self {
+        ListPatch::Batch(patches.into_iter().collect())
     }
 
     /// Construct a ListPatch that pushes the given item onto the end of the list.
@@ -92,8 +137,111 @@ impl<T> ListPatch<T> {
         ListPatch::splice(.., std::iter::empty())
     }
 
+    /// The patches of this patch, with any batches flattened.
+    fn flattened(self) -> Vec<Self> {
+        match self {
+            ListPatch::Batch(patches) => {
+                patches.into_iter().flat_map(ListPatch::flattened).collect()
+            }
+            patch => vec![patch],
+        }
+    }
+
+    /// The length of a list of length `len` after this patch is applied.
+    fn patched_len(&self, len: usize) -> usize {
+        match self {
+            ListPatch::Splice {
+                range: (start, end),
+                replace_with,
+            } => {
+                let start = match start {
+                    Bound::Included(i) => *i,
+                    Bound::Excluded(i) => i + 1,
+                    Bound::Unbounded => 0,
+                };
+                let end = match end {
+                    Bound::Included(i) => i + 1,
+                    Bound::Excluded(i) => *i,
+                    Bound::Unbounded => len,
+                };
+                len - end.min(len).saturating_sub(start) + replace_with.len()
+            }
+            ListPatch::Push(_) => len + 1,
+            ListPatch::Pop => len.saturating_sub(1),
+            ListPatch::Batch(patches) => patches.iter().fold(len, |len, p| p.patched_len(len)),
+            ListPatch::Replace { .. } | ListPatch::Move { .. } | ListPatch::Noop => len,
+        }
+    }
+
+    /// Translate this patch, meant for a list of length `len`, into the patch
+    /// that has the same effect on the reverse of that list.
+    ///
+    /// Splice ranges are mirrored and their replacements reversed, pushes
+    /// become inserts at the front, pops become removals from the front and
+    /// replace and move indices are mirrored. Each patch in a batch is translated for the
+    /// length of the list at that point in the batch.
+    pub fn reversed(self, len: usize) -> Self {
+        match self {
+            ListPatch::Splice {
+                range: (start, end),
+                mut replace_with,
+            } => {
+                let start = match start {
+                    Bound::Included(i) => i,
+                    Bound::Excluded(i) => i + 1,
+                    Bound::Unbounded => 0,
+                };
+                let end = match end {
+                    Bound::Included(i) => i + 1,
+                    Bound::Excluded(i) => i,
+                    Bound::Unbounded => len,
+                };
+                replace_with.reverse();
+                ListPatch::splice(
+                    len.saturating_sub(end)..len.saturating_sub(start),
+                    replace_with,
+                )
+            }
+            ListPatch::Push(t) => ListPatch::insert(0, t),
+            ListPatch::Pop if len == 0 => ListPatch::Noop,
+            ListPatch::Pop => ListPatch::remove(0),
+            ListPatch::Replace { index, value } if index < len => ListPatch::Replace {
+                index: len - 1 - index,
+                value,
+            },
+            ListPatch::Replace { .. } => ListPatch::Noop,
+            ListPatch::Move { from, to } if from < len && to < len => ListPatch::Move {
+                from: len - 1 - from,
+                to: len - 1 - to,
+            },
+            ListPatch::Move { .. } => ListPatch::Noop,
+            ListPatch::Batch(patches) => {
+                let mut len = len;
+                ListPatch::Batch(
+                    patches
+                        .into_iter()
+                        .map(|patch| {
+                            let next_len = patch.patched_len(len);
+                            let patch = patch.reversed(len);
+                            len = next_len;
+                            patch
+                        })
+                        .collect(),
+                )
+            }
+            ListPatch::Noop => ListPatch::Noop,
+        }
+    }
+
     /// Map the patch from `T` to `X`
     pub fn map<F, X>(self, mut f: F) -> ListPatch<X>
+    where
+        F: FnMut(T) -> X,
+    {
+        self.map_mut(&mut f)
+    }
+
+    fn map_mut<F, X>(self, f: &mut F) -> ListPatch<X>
     where
         F: FnMut(T) -> X,
     {
@@ -107,12 +255,27 @@ impl<T> ListPatch<T> {
             },
             ListPatch::Push(value) => ListPatch::Push(f(value)),
             ListPatch::Pop => ListPatch::Pop,
+            ListPatch::Replace { index, value } => ListPatch::Replace {
+                index,
+                value: f(value),
+            },
+            ListPatch::Move { from, to } => ListPatch::Move { from, to },
+            ListPatch::Batch(patches) => {
+                ListPatch::Batch(patches.into_iter().map(|p| p.map_mut(f)).collect())
+            }
             ListPatch::Noop => ListPatch::Noop,
         }
     }
 
     /// Map the patch from `T` to `X`
     pub fn try_map<F, X, E>(self, mut f: F) -> Result<ListPatch<X>, E>
+    where
+        F: FnMut(T) -> Result<X, E>,
+    {
+        self.try_map_mut(&mut f)
+    }
+
+    fn try_map_mut<F, X, E>(self, f: &mut F) -> Result<ListPatch<X>, E>
     where
         F: FnMut(T) -> Result<X, E>,
     {
@@ -133,6 +296,17 @@ impl<T> ListPatch<T> {
             },
             ListPatch::Push(value) => ListPatch::Push(f(value)?),
             ListPatch::Pop => ListPatch::Pop,
+            ListPatch::Replace { index, value } => ListPatch::Replace {
+                index,
+                value: f(value)?,
+            },
+            ListPatch::Move { from, to } => ListPatch::Move { from, to },
+            ListPatch::Batch(patches) => ListPatch::Batch(
+                patches
+                    .into_iter()
+                    .map(|p| p.try_map_mut(f))
+                    .collect::<Result<Vec<_>, E>>()?,
+            ),
             ListPatch::Noop => ListPatch::Noop,
         })
     }
@@ -220,11 +394,161 @@ impl<T> ListPatchApply for Vec<T> {
                 vec![]
             }
             ListPatch::Pop => self.pop().map(|t| vec![t]).unwrap_or_else(|| vec![]),
+            ListPatch::Replace { index, value } => match self.get_mut(index) {
+                Some(t) => vec![std::mem::replace(t, value)],
+                None => vec![],
+            },
+            ListPatch::Move { from, to } => {
+                if from < self.len() && to < self.len() {
+                    let t = self.remove(from);
+                    self.insert(to, t);
+                }
+                vec![]
+            }
+            ListPatch::Batch(patches) => patches
+                .into_iter()
+                .flat_map(|patch| self.list_patch_apply(patch))
+                .collect(),
             ListPatch::Noop => vec![],
         }
     }
 }
 
+/// Items are inserted from whichever end of the deque is closer, so patches
+/// near the front (eg. `ListPatch::insert(0, item)`) are cheap.
+impl<T> ListPatchApply for VecDeque<T> {
+    type Item = T;
+
+    fn list_patch_apply(&mut self, patch: ListPatch<T>) -> Vec<T> {
+        match patch {
+            ListPatch::Splice {
+                range,
+                replace_with,
+            } => {
+                let start = match range.0 {
+                    Bound::Included(i) => i,
+                    Bound::Excluded(i) => i + 1,
+                    Bound::Unbounded => 0,
+                };
+                let removed = self.drain(range).collect::<Vec<T>>();
+                for (i, t) in replace_with.into_iter().enumerate() {
+                    self.insert(start + i, t);
+                }
+                removed
+            }
+            ListPatch::Push(value) => {
+                self.push_back(value);
+                vec![]
+            }
+            ListPatch::Pop => self.pop_back().map(|t| vec![t]).unwrap_or_default(),
+            ListPatch::Replace { index, value } => match self.get_mut(index) {
+                Some(t) => vec![std::mem::replace(t, value)],
+                None => vec![],
+            },
+            ListPatch::Move { from, to } => {
+                if from < self.len() && to < self.len() {
+                    // UNWRAP: safe because we checked the index above
+                    let t = self.remove(from).unwrap();
+                    self.insert(to, t);
+                }
+                vec![]
+            }
+            ListPatch::Batch(patches) => patches
+                .into_iter()
+                .flat_map(|patch| self.list_patch_apply(patch))
+                .collect(),
+            ListPatch::Noop => vec![],
+        }
+    }
+}
+
+/// Translates patches of a list into patches of a copy of that list that is
+/// kept sorted by a key.
+///
+/// Items with equal keys keep the order in which they were added. Only the
+/// added and removed items are patched in the sorted copy, so the other
+/// items are never replaced. A replaced item is replaced where it is and then
+/// repositioned with [`ListPatch::Move`] if its key changed its place.
+pub(crate) struct SortedListPatches<K> {
+    next_id: usize,
+    ids: Vec<usize>,
+    sorted: Vec<(K, usize)>,
+}
+
+impl<K> Default for SortedListPatches<K> {
+    fn default() -> Self {
+        SortedListPatches {
+            next_id: 0,
+            ids: vec![],
+            sorted: vec![],
+        }
+    }
+}
+
+impl<K: Ord> SortedListPatches<K> {
+    /// Translate the patch of the unsorted list into patches of the sorted
+    /// list.
+    pub(crate) fn patch<T>(
+        &mut self,
+        patch: ListPatch<T>,
+        key: impl Fn(&T) -> K,
+    ) -> Vec<ListPatch<T>> {
+        patch
+            .flattened()
+            .into_iter()
+            .flat_map(|patch| self.patch_one(patch, &key))
+            .collect()
+    }
+
+    fn patch_one<T>(&mut self, patch: ListPatch<T>, key: &impl Fn(&T) -> K) -> Vec<ListPatch<T>> {
+        // a replaced item keeps its place in the sorted list until it is moved
+        // to the position of its new key
+        if let ListPatch::Replace { index, value } = patch {
+            let from = match self
+                .ids
+                .get(index)
+                .and_then(|id| self.sorted.iter().position(|(_, i)| i == id))
+            {
+                Some(from) => from,
+                None => return vec![],
+            };
+            let (_, id) = self.sorted.remove(from);
+            let k = key(&value);
+            let to = self.sorted.partition_point(|(sk, _)| sk <= &k);
+            self.sorted.insert(to, (k, id));
+            let mut patches = vec![ListPatch::replace(from, value)];
+            if from != to {
+                patches.push(ListPatch::move_item(from, to));
+            }
+            return patches;
+        }
+
+        let mut added = vec![];
+        let id_patch = patch.map(|t| {
+            let id = self.next_id;
+            self.next_id += 1;
+            added.push((id, t));
+            id
+        });
+        let removed = self.ids.list_patch_apply(id_patch);
+
+        let mut patches = vec![];
+        for id in removed.into_iter() {
+            if let Some(index) = self.sorted.iter().position(|(_, i)| *i == id) {
+                self.sorted.remove(index);
+                patches.push(ListPatch::remove(index));
+            }
+        }
+        for (id, t) in added.into_iter() {
+            let k = key(&t);
+            let index = self.sorted.partition_point(|(sk, _)| sk <= &k);
+            self.sorted.insert(index, (k, id));
+            patches.push(ListPatch::insert(index, t));
+        }
+        patches
+    }
+}
+
 #[cfg(test)]
 mod list {
     use super::*;
@@ -274,6 +598,200 @@ mod list {
         assert_eq!(n, 1);
         assert_eq!(&vs, &[0]);
     }
+
+    #[test]
+    fn replace_patching() {
+        let mut vs = vec![0, 1, 2];
+        assert_eq!(vs.list_patch_apply(ListPatch::replace(1, 666)), vec![1]);
+        assert_eq!(&vs, &[0, 666, 2]);
+        assert!(vs.list_patch_apply(ListPatch::replace(3, 777)).is_empty());
+        assert_eq!(&vs, &[0, 666, 2]);
+        assert_eq!(
+            ListPatch::replace(0, 666).reversed(3),
+            ListPatch::replace(2, 666)
+        );
+    }
+
+    #[test]
+    fn vec_deque_patches_like_vec() {
+        let patches = vec![
+            ListPatch::push(1),
+            ListPatch::push(2),
+            ListPatch::insert(0, 0),
+            ListPatch::splice(1..2, vec![10, 11, 12]),
+            ListPatch::replace(3, 666),
+            ListPatch::move_item(0, 4),
+            ListPatch::remove(1),
+            ListPatch::pop(),
+            ListPatch::batch(vec![ListPatch::insert(0, 7), ListPatch::splice(.., vec![8, 9])]),
+            ListPatch::insert(1, 3),
+            ListPatch::drain(),
+        ];
+        let mut vec = vec![];
+        let mut deque = VecDeque::new();
+        for patch in patches {
+            let expected = vec.list_patch_apply(patch.clone());
+            assert_eq!(deque.list_patch_apply(patch), expected);
+            assert_eq!(deque.iter().copied().collect::<Vec<_>>(), vec);
+        }
+    }
+
+    #[test]
+    fn move_patching() {
+        let mut vs = vec![0, 1, 2, 3];
+        assert!(vs.list_patch_apply(ListPatch::move_item(3, 0)).is_empty());
+        assert_eq!(&vs, &[3, 0, 1, 2]);
+        vs.list_patch_apply(ListPatch::move_item(1, 2));
+        assert_eq!(&vs, &[3, 1, 0, 2]);
+        // out of bounds moves do nothing
+        vs.list_patch_apply(ListPatch::move_item(4, 0));
+        vs.list_patch_apply(ListPatch::move_item(0, 4));
+        assert_eq!(&vs, &[3, 1, 0, 2]);
+    }
+
+    #[test]
+    fn batch_patching() {
+        let mut vs = vec![0, 1, 2];
+        let removed = vs.list_patch_apply(ListPatch::batch(vec![
+            ListPatch::push(3),
+            ListPatch::remove(0),
+            ListPatch::move_item(2, 0),
+            ListPatch::batch(vec![ListPatch::pop(), ListPatch::insert(1, 4)]),
+        ]));
+        assert_eq!(removed, vec![0, 2]);
+        assert_eq!(&vs, &[3, 4, 1]);
+
+        let mapped =
+            ListPatch::batch(vec![ListPatch::push(1), ListPatch::insert(0, 2)]).map(|n| n * 10);
+        assert_eq!(
+            mapped,
+            ListPatch::batch(vec![ListPatch::push(10), ListPatch::insert(0, 20)])
+        );
+    }
+
+    #[test]
+    fn reversed_patching() {
+        let patches = vec![
+            ListPatch::push(0),
+            ListPatch::push(1),
+            ListPatch::push(2),
+            ListPatch::insert(1, 3),
+            ListPatch::batch(vec![
+                ListPatch::push(10),
+                ListPatch::remove(0),
+                ListPatch::splice(1..3, vec![11, 12, 13]),
+                ListPatch::pop(),
+                ListPatch::move_item(0, 2),
+            ]),
+            ListPatch::splice(1..3, vec![4, 5, 6]),
+            ListPatch::remove(0),
+            ListPatch::splice(2.., vec![7]),
+            ListPatch::pop(),
+            ListPatch::splice(..=1, vec![8, 9]),
+            ListPatch::move_item(0, 2),
+            ListPatch::move_item(2, 1),
+            ListPatch::drain(),
+            ListPatch::pop(),
+        ];
+        let mut forward: Vec<usize> = vec![];
+        let mut backward: Vec<usize> = vec![];
+        for patch in patches.into_iter() {
+            backward.list_patch_apply(patch.clone().reversed(forward.len()));
+            forward.list_patch_apply(patch);
+            let mut expected = forward.clone();
+            expected.reverse();
+            assert_eq!(backward, expected);
+        }
+    }
+    #[test]
+    fn sorted_patching() {
+        let patches = vec![
+            ListPatch::push(3),
+            ListPatch::push(1),
+            ListPatch::push(2),
+            ListPatch::insert(1, 0),
+            ListPatch::batch(vec![ListPatch::push(8), ListPatch::pop()]),
+            ListPatch::batch(vec![ListPatch::push(6), ListPatch::insert(0, 2)]),
+            ListPatch::splice(1..3, vec![5, 4, 1]),
+            ListPatch::remove(0),
+            ListPatch::move_item(0, 2),
+            ListPatch::replace(1, 0),
+            ListPatch::replace(0, 7),
+            ListPatch::replace(3, 9),
+            ListPatch::pop(),
+            ListPatch::Noop,
+            ListPatch::drain(),
+        ];
+        let mut sorter = SortedListPatches::default();
+        let mut unsorted: Vec<usize> = vec![];
+        let mut sorted: Vec<usize> = vec![];
+        for patch in patches.into_iter() {
+            for sorted_patch in sorter.patch(patch.clone(), |n| *n) {
+                sorted.list_patch_apply(sorted_patch);
+            }
+            unsorted.list_patch_apply(patch);
+            let mut expected = unsorted.clone();
+            expected.sort();
+            assert_eq!(sorted, expected);
+        }
+    }
+
+    #[test]
+    fn sorted_patching_only_inserts_new_items() {
+        let mut sorter = SortedListPatches::default();
+        let _ = sorter.patch(ListPatch::splice(.., vec![1, 3, 5]), |n: &usize| *n);
+        assert_eq!(
+            sorter.patch(ListPatch::push(4), |n| *n),
+            vec![ListPatch::insert(2, 4)]
+        );
+    }
+
+    #[test]
+    fn sorted_patching_moves_replaced_items() {
+        let mut sorter = SortedListPatches::default();
+        let _ = sorter.patch(ListPatch::splice(.., vec![1, 3, 5]), |n: &usize| *n);
+        assert_eq!(
+            sorter.patch(ListPatch::replace(0, 4), |n| *n),
+            vec![ListPatch::replace(0, 4), ListPatch::move_item(0, 1)]
+        );
+        assert_eq!(
+            sorter.patch(ListPatch::replace(2, 6), |n| *n),
+            vec![ListPatch::replace(2, 6)]
+        );
+    }
+}
+
+type ModifyFn<V> = dyn Fn(Option<&V>) -> Option<V> + Send + Sync;
+
+/// A function from the current value at a key (if any) to its new value.
+///
+/// Used by [`HashPatch::Modify`]. Returning `None` removes the key.
+pub struct HashPatchFn<V>(Arc<ModifyFn<V>>);
+
+impl<V> Clone for HashPatchFn<V> {
+    fn clone(&self) -> Self {
+        HashPatchFn(self.0.clone())
+    }
+}
+
+impl<V> std::fmt::Debug for HashPatchFn<V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("HashPatchFn(..)")
+    }
+}
+
+/// Two functions are only equal if they are the same shared function.
+impl<V> PartialEq for HashPatchFn<V> {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl<V> HashPatchFn<V> {
+    /// Compute the new value from the current one.
+    pub fn call(&self, current: Option<&V>) -> Option<V> {
+        (self.0)(current)
+    }
 }
 
 /// Variants used to patch the items in a hash map.
@@ -283,6 +801,53 @@ pub enum HashPatch<K, V> {
     Insert(K, V),
     /// Remove the value at `K`
     Remove(K),
+    /// Compute a new value at `key` from the existing one.
+    ///
+    /// If `f` returns `None` the key is removed.
+    Modify {
+        /// The key to modify.
+        key: K,
+        /// Computes the new value from the existing one.
+        f: HashPatchFn<V>,
+    },
+}
+
+impl<K, V> HashPatch<K, V> {
+    /// Create a [`HashPatch::Modify`] patch.
+    ///
+    /// ```rust
+    /// use std::collections::HashMap;
+    /// use mogwai::patch::{HashPatch, HashPatchApply};
+    ///
+    /// let mut classes = HashMap::from([("class", "nav-link".to_string())]);
+    /// let _ = classes.hash_patch_apply(HashPatch::modify("class", |class: Option<&String>| {
+    ///     Some(format!("{} active", class?))
+    /// }));
+    /// assert_eq!(classes["class"], "nav-link active");
+    /// ```
+    pub fn modify(key: K, f: impl Fn(Option<&V>) -> Option<V> + Send + Sync + 'static) -> Self {
+        HashPatch::Modify {
+            key,
+            f: HashPatchFn(Arc::new(f)),
+        }
+    }
+
+    /// Resolve a [`HashPatch::Modify`] against the current value at its key,
+    /// producing the equivalent `Insert` or `Remove`.
+    ///
+    /// `Insert` and `Remove` are returned unchanged.
+    pub fn resolve(self, current: impl FnOnce(&K) -> Option<V>) -> Self {
+        match self {
+            HashPatch::Modify { key, f } => {
+                let current = current(&key);
+                match f.call(current.as_ref()) {
+                    Some(v) => HashPatch::Insert(key, v),
+                    None => HashPatch::Remove(key),
+                }
+            }
+            patch => patch,
+        }
+    }
 }
 
 /// Provides `hash_patch_apply`
@@ -321,6 +886,32 @@ where
         match patch {
             HashPatch::Insert(k, v) => self.insert(k, v),
             HashPatch::Remove(k) => self.remove(&k),
+            HashPatch::Modify { key, f } => match f.call(self.get(&key)) {
+                Some(v) => self.insert(key, v),
+                None => self.remove(&key),
+            },
+        }
+    }
+}
+
+impl<K, V> HashPatchApply for BTreeMap<K, V>
+where
+    K: Ord,
+{
+    type Key = K;
+    type Value = V;
+
+    fn hash_patch_apply(
+        &mut self,
+        patch: HashPatch<Self::Key, Self::Value>,
+    ) -> Option<Self::Value> {
+        match patch {
+            HashPatch::Insert(k, v) => self.insert(k, v),
+            HashPatch::Remove(k) => self.remove(&k),
+            HashPatch::Modify { key, f } => match f.call(self.get(&key)) {
+                Some(v) => self.insert(key, v),
+                None => self.remove(&key),
+            },
         }
     }
 }
@@ -353,6 +944,97 @@ where
                     None
                 }
             }
+            HashPatch::Modify { key, f } => {
+                if let Some(i) = self.iter().position(|(k_here, _)| k_here == &key) {
+                    if let Some(v) = f.call(Some(&self[i].1)) {
+                        Some(std::mem::replace(&mut self[i].1, v))
+                    } else {
+                        Some(self.remove(i).1)
+                    }
+                } else {
+                    if let Some(v) = f.call(None) {
+                        self.push((key, v));
+                    }
+                    None
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod hash {
+    use super::*;
+
+    fn add_active(class: Option<&String>) -> Option<String> {
+        Some(match class {
+            Some(class) => format!("{} active", class),
+            None => "active".to_string(),
+        })
+    }
+
+    #[test]
+    fn modify_inserts_when_absent() {
+        let mut map: HashMap<&str, String> = HashMap::new();
+        assert_eq!(
+            map.hash_patch_apply(HashPatch::modify("class", add_active)),
+            None
+        );
+        assert_eq!(map["class"], "active");
+
+        let mut vs: Vec<(&str, String)> = vec![];
+        assert_eq!(
+            vs.hash_patch_apply(HashPatch::modify("class", add_active)),
+            None
+        );
+        assert_eq!(vs, vec![("class", "active".to_string())]);
+    }
+
+    #[test]
+    fn modify_updates_when_present() {
+        let mut map = HashMap::from([("class", "nav-link".to_string())]);
+        assert_eq!(
+            map.hash_patch_apply(HashPatch::modify("class", add_active)),
+            Some("nav-link".to_string())
+        );
+        assert_eq!(map["class"], "nav-link active");
+
+        let mut vs = vec![("id", "a".to_string()), ("class", "nav-link".to_string())];
+        assert_eq!(
+            vs.hash_patch_apply(HashPatch::modify("class", add_active)),
+            Some("nav-link".to_string())
+        );
+        assert_eq!(
+            vs,
+            vec![
+                ("id", "a".to_string()),
+                ("class", "nav-link active".to_string())
+            ]
+        );
+
+        // returning `None` removes the key
+        let _ = vs.hash_patch_apply(HashPatch::modify("id", |_: Option<&String>| None));
+        assert_eq!(vs, vec![("class", "nav-link active".to_string())]);
+    }
+
+    #[test]
+    fn btree_map_patches_iterate_in_key_order() {
+        let mut attribs: BTreeMap<String, String> = BTreeMap::new();
+        for patch in [
+            HashPatch::Insert("title".to_string(), "hi".to_string()),
+            HashPatch::Insert("class".to_string(), "nav-link".to_string()),
+            HashPatch::modify("class".to_string(), add_active),
+            HashPatch::Insert("id".to_string(), "a".to_string()),
+            HashPatch::Remove("id".to_string()),
+        ] {
+            let _ = attribs.hash_patch_apply(patch);
         }
+        assert_eq!(
+            attribs.into_iter().collect::<Vec<_>>(),
+            vec![
+                ("class".to_string(), "nav-link active".to_string()),
+                ("title".to_string(), "hi".to_string()),
+            ]
+        );
     }
 }
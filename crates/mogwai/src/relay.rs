@@ -58,6 +58,7 @@
 use std::sync::{Arc, Mutex};
 
 use anyhow::Context;
+use futures_lite::FutureExt;
 
 use crate::{
     channel::broadcast,
@@ -155,6 +156,25 @@ impl<T: Send> Input<T> {
         let mut lock = self.rx.lock().unwrap();
         lock.take()
     }
+
+    /// Attempt to acquire a stream of updates to this input that starts with
+    /// the last value set before subscribing, if any.
+    ///
+    /// Values set before subscribing that have not been consumed are
+    /// drained, and only the latest is replayed to the stream before live
+    /// updates. This lets a view's first frame be driven by the input itself,
+    /// without an explicit initial value.
+    ///
+    /// Like [`Input::stream`] this returns `Some` the first time it is called
+    /// and `None` each subsequent call.
+    pub fn stream_with_initial(&mut self) -> Option<impl Stream<Item = T> + Send> {
+        let rx = self.rx.lock().unwrap().take()?;
+        let mut initial = None;
+        while let Ok(t) = rx.try_recv() {
+            initial = Some(t);
+        }
+        Some(crate::stream::iter(initial).chain(rx))
+    }
 }
 
 /// An input that fans input values to many consumers.
@@ -228,17 +248,36 @@ impl<T: Clone + Send + Sync> FanInput<T> {
     }
 }
 
+/// The number of events an [`Output`] holds for [`Output::get`] and
+/// [`Output::get_many`] before the oldest are dropped.
+pub const OUTPUT_BUFFER_CAPACITY: usize = 16;
+
 /// An event output from a view.
-#[derive(Clone)]
+///
+/// Each clone of an output buffers up to [`OUTPUT_BUFFER_CAPACITY`] of the
+/// most recent events, which [`Output::get`] and [`Output::get_many`] take
+/// from in order, so each clone sees every event exactly once.
 pub struct Output<T> {
     chan: broadcast::Channel<T>,
+    // this clone's own receiver, shared by `get` and `get_many`
+    rx: Arc<async_lock::Mutex<broadcast::Receiver<T>>>,
+}
+
+impl<T: Clone> Clone for Output<T> {
+    fn clone(&self) -> Self {
+        Self {
+            chan: self.chan.clone(),
+            rx: Arc::new(async_lock::Mutex::new(self.chan.receiver())),
+        }
+    }
 }
 
 impl<T> Default for Output<T> {
     fn default() -> Self {
-        let mut chan = broadcast::Channel::new(1);
+        let mut chan = broadcast::Channel::new(OUTPUT_BUFFER_CAPACITY);
         chan.set_overflow(true);
-        Self { chan }
+        let rx = Arc::new(async_lock::Mutex::new(chan.receiver()));
+        Self { chan, rx }
     }
 }
 
@@ -253,28 +292,91 @@ impl<T: Clone + Send + Sync> Sink<T> for Output<T> {
     }
 
     fn try_send(&self, item: T) -> Result<(), TrySendError> {
-        let sender = self.chan.sender();
-        sender.try_send(item)
+        self.chan.sender().try_send(item)
     }
 }
 
 impl<T: Clone + Send + Sync> Output<T> {
     /// Returns a sink used to send events through the output.
     ///
-    /// This can be used by views to send events downstream.
+    /// This can be used by views to send events downstream. Events can be
+    /// transformed on the way in with [`SinkExt::contra_map`], reduced into
+    /// some running state with [`SinkExt::contra_fold`], or transformed
+    /// and filtered with [`SinkExt::contra_filter_map`], where returning
+    /// `None` drops the event:
+    ///
+    /// ```rust
+    /// use mogwai::prelude::*;
+    ///
+    /// let output = Output::<u32>::default();
+    /// let evens = output
+    ///     .sink()
+    ///     .contra_filter_map(|n: u32| if n % 2 == 0 { Some(n) } else { None });
+    /// evens.try_send(1).unwrap();
+    /// evens.try_send(2).unwrap();
+    /// assert_eq!(output.get_many(), vec![2]);
+    /// ```
+    ///
+    /// [`SinkExt::contra_map`]: crate::sink::SinkExt::contra_map
+    /// [`SinkExt::contra_filter_map`]: crate::sink::SinkExt::contra_filter_map
+    /// [`SinkExt::contra_fold`]: crate::sink::SinkExt::contra_fold
     pub fn sink(&self) -> impl Sink<T> + Send + Sync {
         self.chan.sender()
     }
 
-    /// Return the next event occurrence.
+    /// Return the next event occurrence, waiting for one if none is
+    /// buffered.
     ///
     /// A returned value of `None` means the output is no longer
     /// operating.
     pub async fn get(&self) -> Option<T> {
-        let mut rx = self.chan.receiver();
+        let mut rx = self.rx.lock().await;
         rx.next().await
     }
 
+    /// Return all buffered event occurrences without waiting.
+    ///
+    /// These are the events not yet returned by [`Output::get`], so the two
+    /// can be used together, eg. to wait for an event and then handle any
+    /// that queued up behind it. Returns nothing while a call to `get` on
+    /// this output is waiting, as that call takes the next event.
+    pub fn get_many(&self) -> Vec<T> {
+        let mut items = vec![];
+        if let Some(mut rx) = self.rx.try_lock() {
+            loop {
+                match rx.inner.try_recv() {
+                    Ok(item) => items.push(item),
+                    Err(async_broadcast::TryRecvError::Overflowed(_)) => {}
+                    Err(_) => break,
+                }
+            }
+        }
+        items
+    }
+
+    /// Return the latest event occurrence once no other event has occurred
+    /// for `millis` milliseconds.
+    ///
+    /// This coalesces bursts of events, eg. `input` events while a user is
+    /// typing, and can be used in place of [`Output::get`] in a logic loop.
+    ///
+    /// A returned value of `None` means the output is no longer
+    /// operating.
+    pub async fn get_debounced(&self, millis: u64) -> Option<T> {
+        let mut latest = self.get().await?;
+        loop {
+            let next = async { Some(self.get().await) };
+            let quiet = async {
+                crate::time::wait_millis(millis).await;
+                None
+            };
+            match next.or(quiet).await {
+                Some(Some(t)) => latest = t,
+                Some(None) | None => return Some(latest),
+            }
+        }
+    }
+
     /// Return a stream of event occurrences.
     pub fn get_stream(&self) -> impl Stream<Item = T> + Send + Sync {
         self.chan.receiver()
@@ -290,3 +392,121 @@ impl<T: Clone + Send + Sync> Output<T> {
         self.chan.receiver()
     }
 }
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod test {
+    use super::*;
+    use crate::{sink::SinkExt, time::wait_millis};
+
+    #[test]
+    fn output_get_debounced_coalesces_bursts() {
+        let output = Output::<u32>::default();
+        crate::future::block_on(async {
+            let receiving = async {
+                let mut delivered = vec![];
+                loop {
+                    let timeout = async {
+                        wait_millis(150).await;
+                        None
+                    };
+                    match output.get_debounced(30).or(timeout).await {
+                        Some(n) => delivered.push(n),
+                        None => break,
+                    }
+                }
+                delivered
+            };
+            let sending = async {
+                for n in 0..3 {
+                    wait_millis(5).await;
+                    output.send(n).await.unwrap();
+                }
+            };
+            let (delivered, ()) = futures_lite::future::zip(receiving, sending).await;
+            assert_eq!(delivered, vec![2]);
+        });
+    }
+
+    #[test]
+    fn output_get_many_drains_buffer() {
+        let output = Output::<u32>::default();
+        assert!(output.get_many().is_empty());
+        crate::future::block_on(async {
+            for n in 0..3 {
+                output.send(n).await.unwrap();
+            }
+            assert_eq!(output.get_many(), vec![0, 1, 2]);
+            assert!(output.get_many().is_empty());
+
+            output.send(3).await.unwrap();
+            assert_eq!(output.get().await, Some(3));
+            assert!(output.get_many().is_empty());
+        });
+    }
+
+    #[test]
+    fn output_get_then_get_many_delivers_each_event_once() {
+        let output = Output::<u32>::default();
+        crate::future::block_on(async {
+            for n in 0..4 {
+                output.send(n).await.unwrap();
+            }
+            assert_eq!(output.get().await, Some(0));
+            assert_eq!(output.get_many(), vec![1, 2, 3]);
+
+            let sending = async {
+                futures_lite::future::yield_now().await;
+                output.send(4).await.unwrap();
+                output.send(5).await.unwrap();
+            };
+            let (got, ()) = futures_lite::future::zip(output.get(), sending).await;
+            assert_eq!(got, Some(4));
+            assert_eq!(output.get_many(), vec![5]);
+        });
+    }
+
+    #[test]
+    fn output_clones_each_get_every_event() {
+        let output = Output::<u32>::default();
+        let other = output.clone();
+        crate::future::block_on(async {
+            output.send(1).await.unwrap();
+            output.send(2).await.unwrap();
+
+            assert_eq!(output.get().await, Some(1));
+            assert_eq!(other.get().await, Some(1));
+            assert_eq!(other.get_many(), vec![2]);
+            assert_eq!(output.get_many(), vec![2]);
+        });
+    }
+
+    #[test]
+    fn output_sink_can_filter_events() {
+        let output = Output::<u32>::default();
+        let small = output
+            .sink()
+            .contra_filter_map(|n: u32| (n < 3).then_some(n * 10));
+        crate::future::block_on(async {
+            for n in 0..6 {
+                small.try_send(n).unwrap();
+            }
+            assert_eq!(output.get_many(), vec![0, 10, 20]);
+        });
+    }
+
+    #[test]
+    fn output_sink_can_fold_events() {
+        let output = Output::<u32>::default();
+        let clicks = output.sink().contra_fold(0, |count: &mut u32, ()| {
+            *count += 1;
+            *count
+        });
+        crate::future::block_on(async {
+            for _ in 0..3 {
+                clicks.try_send(()).unwrap();
+            }
+            clicks.try_send(()).unwrap();
+            assert_eq!(output.get_many(), vec![1, 2, 3, 4]);
+        });
+    }
+}
@@ -87,6 +87,25 @@ pub trait SinkExt<Item>: Sink<Item> {
             _y: PhantomData,
         }
     }
+
+    /// Extend this sink using a folding function.
+    ///
+    /// This composes the fold function _in front of the sink_, consuming a sink that takes
+    /// `Item` and returning a sink that takes `S`. Each item sent updates the state `T`,
+    /// starting at `init`, and the function's output is sent downstream. The state is
+    /// updated even if sending downstream fails.
+    fn contra_fold<S, T, F>(self, init: T, f: F) -> ContraFold<Self, S, T, F>
+    where
+        Self: Sized,
+        F: Fn(&mut T, S) -> Item,
+    {
+        ContraFold {
+            fold: f,
+            state: std::sync::Mutex::new(init),
+            sink: self,
+            _x: PhantomData,
+        }
+    }
 }
 
 impl<S: ?Sized + Sink<Item>, Item> Sink<Item> for Box<S> {
@@ -174,6 +193,42 @@ where
     }
 }
 
+/// Type for supporting contravariant folded sinks.
+pub struct ContraFold<S, X, T, F> {
+    sink: S,
+    state: std::sync::Mutex<T>,
+    fold: F,
+    _x: PhantomData<X>,
+}
+
+impl<S, X, T, Y, F> Sink<X> for ContraFold<S, X, T, F>
+where
+    S: Sink<Y> + Unpin,
+    F: Fn(&mut T, X) -> Y + Unpin,
+    X: Unpin,
+    Y: Unpin,
+{
+    fn send(
+        &self,
+        item: X,
+    ) -> Pin<Box<dyn Future<Output = Result<(), SendError>> + Send + '_>> {
+        let item = {
+            // UNWRAP: if we can't get this lock, we want the program to panic
+            let mut state = self.state.lock().unwrap();
+            (self.fold)(&mut state, item)
+        };
+        self.sink.send(item)
+    }
+
+    fn try_send(&self, item: X) -> Result<(), TrySendError> {
+        let item = {
+            let mut state = self.state.try_lock().map_err(|_| TrySendError::Busy)?;
+            (self.fold)(&mut state, item)
+        };
+        self.sink.try_send(item)
+    }
+}
+
 /// Contravariant functor extensions for types that implement [`Sink`].
 #[cfg(all(not(target_arch = "wasm32"), test))]
 mod test {
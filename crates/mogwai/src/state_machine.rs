@@ -0,0 +1,106 @@
+//! Typed state machines that views can follow.
+//!
+//! Many components are state machines, eg. a request that is loading, then
+//! loaded or failed. A [`StateMachine`] declares its transitions up front,
+//! is driven by events and streams its states, so a view bound with
+//! [`ViewBuilder::with_state_machine`](crate::view::ViewBuilder::with_state_machine)
+//! follows along.
+//!
+//! ```rust
+//! use mogwai::state_machine::StateMachine;
+//!
+//! #[derive(Clone, Debug, PartialEq)]
+//! enum Door {
+//!     Open,
+//!     Closed,
+//! }
+//!
+//! enum Push {
+//!     In,
+//!     Out,
+//! }
+//!
+//! let door = StateMachine::new(Door::Closed, |door: &Door, push: Push| match (door, push) {
+//!     (Door::Closed, Push::In) => Some(Door::Open),
+//!     (Door::Open, Push::Out) => Some(Door::Closed),
+//!     _ => None,
+//! });
+//! mogwai::future::block_on(async {
+//!     assert!(!door.transition(Push::Out).await);
+//!     assert!(door.transition(Push::In).await);
+//!     assert_eq!(door.current(), Some(Door::Open));
+//! });
+//! ```
+use std::sync::Arc;
+
+use async_lock::Mutex;
+
+use crate::{model::Model, stream::Stream};
+
+type Transition<S, E> = Arc<dyn Fn(&S, E) -> Option<S> + Send + Sync>;
+
+/// A state machine with states `S` that transitions on events `E`.
+///
+/// Clones refer to the same machine.
+pub struct StateMachine<S, E> {
+    state: Model<S>,
+    transition: Transition<S, E>,
+    // serializes transitions, which read then replace the state
+    transitioning: Arc<Mutex<()>>,
+}
+
+impl<S, E> Clone for StateMachine<S, E> {
+    fn clone(&self) -> Self {
+        StateMachine {
+            state: self.state.clone(),
+            transition: self.transition.clone(),
+            transitioning: self.transitioning.clone(),
+        }
+    }
+}
+
+impl<S: Clone + PartialEq + Send + Sync, E> StateMachine<S, E> {
+    /// Create a new state machine in the `initial` state.
+    ///
+    /// `transition` returns the state to move to when the given event occurs
+    /// in the given state, or `None` if the event is ignored in that state.
+    pub fn new(
+        initial: S,
+        transition: impl Fn(&S, E) -> Option<S> + Send + Sync + 'static,
+    ) -> Self {
+        StateMachine {
+            state: Model::new(initial),
+            transition: Arc::new(transition),
+            transitioning: Default::default(),
+        }
+    }
+
+    /// Handle an event, returning whether the machine changed state.
+    ///
+    /// Ignored events don't produce a state on the stream.
+    pub async fn transition(&self, event: E) -> bool {
+        let _transitioning = self.transitioning.lock().await;
+        let next = (self.transition)(&*self.state.read().await, event);
+        if let Some(next) = next {
+            self.state.replace(next).await;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Attempt to get a clone of the current state.
+    ///
+    /// This will fail if the machine is actively transitioning.
+    pub fn current(&self) -> Option<S> {
+        self.state.current()
+    }
+
+    /// Produce a stream of states, starting with the current state.
+    ///
+    /// Like [`Model::stream`], only the most recent state is guaranteed to be
+    /// sent.
+    pub fn stream(&self) -> impl Stream<Item = S> + Send + Sync {
+        self.state.stream()
+    }
+}
@@ -325,6 +325,318 @@ pub async fn wait_until_next_for<T>(
     }
 }
 
+/// Run the fallible future produced by the given function, retrying on error
+/// up to `retries` times with exponential backoff.
+///
+/// After the first failure this waits `base`, after the second `base * 2`,
+/// then `base * 4` and so on. Returns the first success, or the last error
+/// once all retries have been exhausted.
+pub async fn retry_with_backoff<T, A: Future<Output = anyhow::Result<T>>>(
+    retries: usize,
+    base: std::time::Duration,
+    mut f: impl FnMut() -> A,
+) -> anyhow::Result<T> {
+    let mut attempt: u32 = 0;
+    loop {
+        match f().await {
+            Ok(t) => return Ok(t),
+            Err(e) if attempt as usize >= retries => return Err(e),
+            Err(_) => {
+                let backoff = base.saturating_mul(2u32.saturating_pow(attempt));
+                let _ = wait_millis(backoff.as_millis() as u64).await;
+                attempt += 1;
+            }
+        }
+    }
+}
+
+/// Wait for the next item of the stream, or for `millis` milliseconds to pass,
+/// whichever comes first.
+async fn next_or_wait<S: Stream + Unpin>(st: &mut S, millis: u64) -> Either<Option<S::Item>, ()> {
+    let next = async { Either::Left(st.next().await) };
+    let wait = async {
+        let _ = wait_millis(millis).await;
+        Either::Right(())
+    };
+    next.or(wait).await
+}
+
+/// Debounce a stream, only yielding an item once `millis` milliseconds have
+/// passed without a newer item arriving.
+///
+/// Each item replaces the one before it, so a burst of items yields only the
+/// last item of the burst. If the stream ends during a burst, the last item is
+/// yielded right away.
+///
+/// This is useful for text inputs that feed a search, where only the value
+/// after the user stops typing matters.
+pub fn debounce_stream<S: Stream>(stream: S, millis: u64) -> impl Stream<Item = S::Item> {
+    futures_lite::stream::unfold(Some(Box::pin(stream)), move |st| async move {
+        let mut st = st?;
+        let mut latest = st.next().await?;
+        loop {
+            match next_or_wait(&mut st, millis).await {
+                Either::Left(Some(item)) => latest = item,
+                Either::Left(None) => return Some((latest, None)),
+                Either::Right(()) => return Some((latest, Some(st))),
+            }
+        }
+    })
+}
+
+/// Throttle a stream, yielding at most one item every `millis` milliseconds.
+///
+/// The first item is yielded right away. Items that arrive before `millis`
+/// milliseconds have passed replace one another, and the latest of them is
+/// yielded when the time is up, so the stream's final item is never lost.
+pub fn throttle_stream<S: Stream>(stream: S, millis: u64) -> impl Stream<Item = S::Item> {
+    // (stream, item waiting for the window to close, when the window closes)
+    let state = (Some(Box::pin(stream)), None, 0.0);
+    futures_lite::stream::unfold(state, move |(st, mut pending, window_end)| async move {
+        let mut st = st?;
+        loop {
+            if pending.is_none() {
+                let item = st.next().await?;
+                let t = now();
+                if t >= window_end {
+                    return Some((item, (Some(st), None, t + millis as f64)));
+                }
+                pending = Some(item);
+            } else {
+                let remaining = (window_end - now()).max(0.0).ceil() as u64;
+                match next_or_wait(&mut st, remaining).await {
+                    Either::Left(Some(item)) => pending = Some(item),
+                    Either::Left(None) => return pending.map(|item| (item, (None, None, 0.0))),
+                    Either::Right(()) => {
+                        let item = pending.take()?;
+                        return Some((item, (Some(st), None, now() + millis as f64)));
+                    }
+                }
+            }
+        }
+    })
+}
+
+struct IntervalState {
+    period: u64,
+    paused: bool,
+    // bumped whenever the period changes, so pending waits can be restarted
+    epoch: usize,
+    waker: Option<Waker>,
+}
+
+/// A stream that yields `()` every `millis` milliseconds, until dropped.
+///
+/// The interval can be paused, resumed and have its period changed while it
+/// is running. Cloning an interval shares these controls, so a clone can be
+/// moved into another task to drive the original. Only the most recently
+/// polled clone is woken by those controls, so poll a single clone as a
+/// stream and use the others only as controls.
+///
+/// Works on both wasm32 and native targets.
+pub struct Interval {
+    state: Arc<std::sync::Mutex<IntervalState>>,
+    epoch: usize,
+    wait: Option<Pin<Box<dyn Future<Output = f64> + Send>>>,
+}
+
+impl Clone for Interval {
+    fn clone(&self) -> Self {
+        Interval {
+            state: self.state.clone(),
+            epoch: self.epoch,
+            wait: None,
+        }
+    }
+}
+
+impl Interval {
+    /// Create a new interval that ticks every `millis` milliseconds.
+    pub fn new(millis: u64) -> Self {
+        Interval {
+            state: Arc::new(std::sync::Mutex::new(IntervalState {
+                period: millis,
+                paused: false,
+                epoch: 0,
+                waker: None,
+            })),
+            epoch: 0,
+            wait: None,
+        }
+    }
+
+    /// Stop ticking until [`Interval::resume`] is called.
+    ///
+    /// The tick in progress is discarded.
+    pub fn pause(&self) {
+        self.state.lock().unwrap().paused = true;
+    }
+
+    /// Start ticking again after a [`Interval::pause`].
+    ///
+    /// The next tick comes one full period after resuming.
+    pub fn resume(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.paused = false;
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+
+    /// Whether the interval is currently paused.
+    pub fn is_paused(&self) -> bool {
+        self.state.lock().unwrap().paused
+    }
+
+    /// Change the period of the interval.
+    ///
+    /// The tick in progress is restarted with the new period.
+    pub fn set_period(&self, millis: u64) {
+        let mut state = self.state.lock().unwrap();
+        state.period = millis;
+        state.epoch = state.epoch.wrapping_add(1);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl Stream for Interval {
+    type Item = ();
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let mut state = this.state.lock().unwrap();
+        state.waker = Some(cx.waker().clone());
+        if state.paused {
+            this.wait = None;
+            return Poll::Pending;
+        }
+        if this.epoch != state.epoch {
+            this.epoch = state.epoch;
+            this.wait = None;
+        }
+        let period = state.period;
+        drop(state);
+
+        let wait = this
+            .wait
+            .get_or_insert_with(|| Box::pin(wait_millis(period)));
+        match wait.poll(cx) {
+            Poll::Ready(_) => {
+                this.wait = None;
+                Poll::Ready(Some(()))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod nonwasm {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use super::*;
+
+    #[test]
+    fn retries_until_success() {
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let result = futures_lite::future::block_on(retry_with_backoff(
+            3,
+            std::time::Duration::from_millis(1),
+            || {
+                let attempts = attempts.clone();
+                async move {
+                    if attempts.fetch_add(1, Ordering::SeqCst) < 2 {
+                        anyhow::bail!("not yet")
+                    }
+                    Ok("done")
+                }
+            },
+        ));
+        assert_eq!(result.unwrap(), "done");
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn gives_up_after_retries() {
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let result: anyhow::Result<()> = futures_lite::future::block_on(retry_with_backoff(
+            2,
+            std::time::Duration::from_millis(1),
+            || {
+                let attempts = attempts.clone();
+                async move {
+                    attempts.fetch_add(1, Ordering::SeqCst);
+                    anyhow::bail!("never")
+                }
+            },
+        ));
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    /// Yields `items` with a delay in milliseconds before each one.
+    fn timed<T>(items: Vec<(u64, T)>) -> impl Stream<Item = T> {
+        futures_lite::stream::iter(items).then(|(delay, item)| async move {
+            let _ = wait_millis(delay).await;
+            item
+        })
+    }
+
+    #[test]
+    fn debounce_yields_last_of_each_burst() {
+        let stream = debounce_stream(timed(vec![(0, 1), (0, 2), (0, 3), (100, 4), (0, 5)]), 20);
+        let items = futures_lite::future::block_on(stream.collect::<Vec<_>>());
+        assert_eq!(items, vec![3, 5]);
+    }
+
+    #[test]
+    fn throttle_yields_first_and_latest_per_window() {
+        let stream = throttle_stream(timed(vec![(0, 1), (0, 2), (0, 3), (200, 4)]), 50);
+        let items = futures_lite::future::block_on(stream.collect::<Vec<_>>());
+        assert_eq!(items, vec![1, 3, 4]);
+    }
+
+    #[test]
+    fn adapters_are_send() {
+        fn sendable<T: Send>(_: T) {}
+        sendable(debounce_stream(futures_lite::stream::iter(0..3), 1));
+        sendable(throttle_stream(futures_lite::stream::iter(0..3), 1));
+        sendable(Interval::new(1));
+    }
+
+    #[test]
+    fn interval_pauses_and_resumes() {
+        futures_lite::future::block_on(async {
+            let mut interval = Interval::new(10);
+            for _ in 0..3 {
+                assert!(wait_until_next_for(1.0, &mut interval).await.is_ok());
+            }
+
+            interval.pause();
+            assert!(interval.is_paused());
+            assert!(
+                wait_until_next_for(0.1, &mut interval).await.is_err(),
+                "ticked while paused"
+            );
+
+            let controls = interval.clone();
+            controls.resume();
+            assert!(!interval.is_paused());
+            assert!(wait_until_next_for(1.0, &mut interval).await.is_ok());
+
+            interval.set_period(60_000);
+            assert!(wait_until_next_for(0.1, &mut interval).await.is_err());
+            controls.set_period(10);
+            assert!(wait_until_next_for(1.0, &mut interval).await.is_ok());
+        });
+    }
+}
+
 #[cfg(all(test, target_arch = "wasm32"))]
 mod test {
     use super::*;
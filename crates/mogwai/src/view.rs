@@ -9,9 +9,10 @@ use std::{
 };
 
 use crate::{
-    model::Model,
-    patch::{HashPatch, ListPatch},
+    model::{ListPatchModel, Model},
+    patch::{HashPatch, ListPatch, ListPatchApply, SortedListPatches},
     sink::{Sink, SinkExt},
+    state_machine::StateMachine,
     stream::{Stream, StreamExt},
 };
 use anyhow::Context;
@@ -160,10 +161,12 @@ impl AnyEvent {
         }
     }
 
+    /// Borrow the inner event as `T`, without cloning or consuming it.
     pub fn downcast_ref<T: Any + Send + Sync + Clone>(&self) -> Option<&T> {
         self.inner.downcast_ref::<T>()
     }
 
+    /// Mutably borrow the inner event as `T`.
     pub fn downcast_mut<T: Any + Send + Sync + Clone>(&mut self) -> Option<&mut T> {
         self.inner.downcast_mut::<T>()
     }
@@ -244,6 +247,66 @@ mod exhaust {
     }
 }
 
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod throttle {
+    use std::sync::{Arc, Mutex};
+
+    use futures_lite::FutureExt;
+
+    use crate::{model::Model, stream::StreamExt, view::*};
+
+    #[test]
+    fn throttled_model_text_limits_updates() {
+        let model = Model::new(0u32);
+        let mut builder = ViewBuilder::text("").with_throttled_model_text(&model, 20.0);
+        let mut updates = builder.updates.pop().unwrap();
+        let texts: Arc<Mutex<Vec<String>>> = Default::default();
+
+        futures_lite::future::block_on(async {
+            let read = async {
+                while let Some(update) = updates.next().await {
+                    if let Update::Text(text) = update {
+                        texts.lock().unwrap().push(text);
+                    }
+                }
+            };
+            let write = async {
+                for n in 1..=200u32 {
+                    model.visit_mut(|t| *t = n).await;
+                    let _ = crate::time::wait_millis(1).await;
+                }
+                let _ = crate::time::wait_millis(100).await;
+            };
+            read.or(write).await;
+        });
+
+        let texts = texts.lock().unwrap();
+        assert!(texts.len() < 50, "too many updates: {}", texts.len());
+        assert_eq!(texts.last().map(String::as_str), Some("200"));
+    }
+}
+
+/// Generate an id that is unique within this process, for linking views by
+/// id, eg. with [`ViewBuilder::with_aria_describedby`].
+///
+/// Ids are `prefix` followed by a dash and a counter.
+pub fn unique_id(prefix: &str) -> String {
+    static NEXT_ID: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+    let id = NEXT_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    format!("{}-{}", prefix, id)
+}
+
+/// The order in which [`ViewBuilder::with_suspense_list`] reveals its
+/// resolved sections.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RevealOrder {
+    /// Reveal sections top to bottom, each as soon as it and all the
+    /// sections above it have resolved.
+    Forwards,
+    /// Reveal all sections at once, after they have all resolved.
+    Together,
+}
+
 /// An enumeration of values that ViewBuilders accept.
 pub enum MogwaiValue<S, St> {
     /// An owned string.
@@ -334,6 +397,12 @@ impl From<String> for MogwaiValue<String, PinBoxStream<String>> {
     }
 }
 
+impl From<Option<String>> for MogwaiValue<Option<String>, PinBoxStream<Option<String>>> {
+    fn from(s: Option<String>) -> Self {
+        MogwaiValue::Owned(s)
+    }
+}
+
 impl<S, St> From<St> for MogwaiValue<S, St>
 where
     S: Send + 'static,
@@ -371,12 +440,62 @@ where
     }
 }
 
+impl<'a, St> From<(&'a str, St)> for MogwaiValue<Option<String>, St>
+where
+    St: Stream<Item = Option<String>>,
+{
+    fn from(s: (&'a str, St)) -> Self {
+        MogwaiValue::OwnedAndStream(Some(s.0.to_owned()), s.1)
+    }
+}
+
+impl<St> From<(String, St)> for MogwaiValue<Option<String>, St>
+where
+    St: Stream<Item = Option<String>>,
+{
+    fn from(s: (String, St)) -> Self {
+        MogwaiValue::OwnedAndStream(Some(s.0), s.1)
+    }
+}
+
+/// A value for a single style property, see [`ViewBuilder::with_single_style`].
+///
+/// This is implemented for anything that converts into a [`MogwaiValue`] of
+/// `String`, or of `Option<String>` where `None` removes the property. The
+/// `Marker` type only tells the two apart.
+pub trait SingleStyleValue<Marker> {
+    /// Add this value to the builder as the style property `key`.
+    fn add_to_builder(self, builder: ViewBuilder, key: String) -> ViewBuilder;
+}
+
+impl<T, St> SingleStyleValue<(String, St)> for T
+where
+    T: Into<MogwaiValue<String, St>>,
+    St: Stream<Item = String> + Send + 'static,
+{
+    fn add_to_builder(self, builder: ViewBuilder, key: String) -> ViewBuilder {
+        builder.with_single_style_stream(key, self)
+    }
+}
+
+impl<T, St> SingleStyleValue<(Option<String>, St)> for T
+where
+    T: Into<MogwaiValue<Option<String>, St>>,
+    St: Stream<Item = Option<String>> + Send + 'static,
+{
+    fn add_to_builder(self, builder: ViewBuilder, key: String) -> ViewBuilder {
+        builder.with_optional_style_stream(key, self)
+    }
+}
+
 /// The starting identity of a view.
 #[derive(Debug)]
 pub enum ViewIdentity {
     Branch(Cow<'static, str>),
     NamespacedBranch(Cow<'static, str>, Cow<'static, str>),
     Leaf(String),
+    /// A list of children without a wrapping element.
+    Fragment,
 }
 
 pub type MogwaiFuture<T> = Pin<Box<dyn Future<Output = T> + Send + 'static>>;
@@ -471,6 +590,38 @@ impl ViewBuilder {
         matches!(self.identity, ViewIdentity::Leaf(_))
     }
 
+    /// Returns whether this builder is a fragment.
+    pub fn is_fragment(&self) -> bool {
+        matches!(self.identity, ViewIdentity::Fragment)
+    }
+
+    /// Create a new fragment builder, a list of children without a wrapping
+    /// element.
+    ///
+    /// When a fragment is appended into another builder its children are
+    /// appended in its place. Only the fragment's children and tasks are kept,
+    /// anything else added to it (like attributes or styles) is dropped with a
+    /// warning. A fragment that is built on its own renders as just its
+    /// children.
+    ///
+    /// Fragments cannot be patched in as children with
+    /// [`ViewBuilder::with_child_stream`], as a fragment would be one item of
+    /// the patched list but many nodes in the view. Patch in its children
+    /// instead.
+    pub fn fragment(children: Vec<ViewBuilder>) -> Self {
+        ViewBuilder {
+            identity: ViewIdentity::Fragment,
+            initial_values: Default::default(),
+            updates: Default::default(),
+            post_build_ops: vec![],
+            view_sinks: vec![],
+            listeners: vec![],
+            tasks: vec![],
+            hydration_root: None,
+        }
+        .append_iter(children)
+    }
+
     /// Create a new container element builder.
     pub fn element(tag: impl Into<Cow<'static, str>>) -> Self {
         ViewBuilder {
@@ -531,6 +682,38 @@ impl ViewBuilder {
         self
     }
 
+    /// Adds an asynchronous task that can be cancelled.
+    ///
+    /// The task runs until it completes, the view is dropped or the returned
+    /// [`TaskHandle`](crate::future::TaskHandle) is aborted or dropped. Be
+    /// sure to keep the handle around, binding it to `_` drops it
+    /// immediately.
+    pub fn with_task_handle(
+        self,
+        f: impl Future<Output = ()> + Send + 'static,
+    ) -> (Self, crate::future::TaskHandle) {
+        let (handle, task) = crate::future::TaskHandle::new(f);
+        (self.with_task(task), handle)
+    }
+
+    /// Adds an asynchronous task that retries the fallible future produced
+    /// by `f` up to `retries` times, with exponential backoff starting at
+    /// `base`.
+    ///
+    /// See [`retry_with_backoff`](crate::time::retry_with_backoff). If every
+    /// attempt fails the task gives up and logs the last error.
+    pub fn with_retry_task<F, Fut>(self, retries: usize, base: std::time::Duration, f: F) -> Self
+    where
+        F: Fn() -> Fut + Send + 'static,
+        Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+    {
+        self.with_task(async move {
+            if let Err(e) = crate::time::retry_with_backoff(retries, base, f).await {
+                log::error!("task failed after {} retries: {}", retries, e);
+            }
+        })
+    }
+
     /// Add a stream to set the text of this builder.
     pub fn with_text_stream<St: Stream<Item = String> + Send + 'static>(
         mut self,
@@ -546,6 +729,51 @@ impl ViewBuilder {
         self
     }
 
+    /// Add an initial value and a stream of values to set the text of this
+    /// builder, formatted with the given function.
+    ///
+    /// ```rust
+    /// use mogwai::{channel::broadcast, prelude::*};
+    ///
+    /// let (_tx, rx) = broadcast::bounded::<u32>(1);
+    /// let _builder = ViewBuilder::text("")
+    ///     .with_formatted_text(0, rx, |n| format!("Count: {}", n));
+    /// ```
+    pub fn with_formatted_text<T>(
+        self,
+        initial: T,
+        st: impl Stream<Item = T> + Send + 'static,
+        fmt: impl Fn(&T) -> String + Send + 'static,
+    ) -> Self {
+        let text = fmt(&initial);
+        self.with_text_stream((text, st.map(move |t| fmt(&t))))
+    }
+
+    /// Set the text of this builder to the value of the model, updating at
+    /// most `max_hz` times per second.
+    ///
+    /// This is useful for values that change much faster than they can be
+    /// read, eg. a frame rate counter. The model's stream only holds its latest
+    /// value, so changes between samples are skipped rather than queued.
+    pub fn with_throttled_model_text<T>(self, model: &Model<T>, max_hz: f64) -> Self
+    where
+        T: Clone + PartialEq + Send + Sync + std::fmt::Display + 'static,
+    {
+        let period = 1.0 / max_hz;
+        let initial = model.current().map(|t| t.to_string()).unwrap_or_default();
+        let samples = futures_lite::stream::unfold(
+            (model.stream(), false),
+            move |(mut st, should_wait)| async move {
+                if should_wait {
+                    let _ = crate::time::wait_secs(period).await;
+                }
+                let t = st.next().await?;
+                Some((t.to_string(), (st, true)))
+            },
+        );
+        self.with_text_stream((initial, samples))
+    }
+
     /// Add a stream to patch the attributes of this builder.
     pub fn with_attrib_stream<St: Stream<Item = HashPatch<String, String>> + Send + 'static>(
         mut self,
@@ -581,6 +809,146 @@ impl ViewBuilder {
         self
     }
 
+    /// Add a stream to set or remove a single attribute of this builder.
+    ///
+    /// `Some(value)` sets the attribute and `None` removes it entirely, which
+    /// is useful for `aria-*` attributes that should be absent rather than
+    /// empty.
+    pub fn with_optional_attrib_stream<St: Stream<Item = Option<String>> + Send + 'static>(
+        mut self,
+        k: impl Into<String>,
+        st: impl Into<MogwaiValue<Option<String>, St>>,
+    ) -> Self {
+        let key = k.into();
+        let patch = move |may_val: Option<String>| match may_val {
+            Some(val) => HashPatch::Insert(key.clone(), val),
+            None => HashPatch::Remove(key.clone()),
+        };
+        let (may_val, may_st) = st.into().split();
+        if let Some(val) = may_val {
+            self.initial_values.push(Update::Attribute(patch(val)));
+        }
+        if let Some(st) = may_st {
+            self.updates
+                .push(Box::pin(st.map(move |v| Update::Attribute(patch(v)))));
+        }
+        self
+    }
+
+    /// Add a stream to add or remove a single class of this builder.
+    ///
+    /// `true` adds `name` to the `class` attribute and `false` removes it,
+    /// leaving any other classes untouched. This is what
+    /// `class:<name> = ...` in `rsx!` expands to.
+    ///
+    /// ## Warning
+    /// Setting the whole `class` attribute, eg. from a stream given to
+    /// [`ViewBuilder::with_single_attrib_stream`], drops any toggled class
+    /// until its toggle next yields. For this reason `rsx!` only allows
+    /// toggles next to a string literal `class`.
+    pub fn with_class_toggle<St: Stream<Item = bool> + Send + 'static>(
+        mut self,
+        name: impl Into<String>,
+        st: impl Into<MogwaiValue<bool, St>>,
+    ) -> Self {
+        let name = name.into();
+        let patch = move |on: bool| {
+            let name = name.clone();
+            Update::Attribute(HashPatch::modify(
+                "class".to_string(),
+                move |class: Option<&String>| {
+                    let mut classes = class
+                        .map(|c| c.split_whitespace().collect::<Vec<_>>())
+                        .unwrap_or_default();
+                    classes.retain(|c| *c != name);
+                    if on {
+                        classes.push(&name);
+                    }
+                    (!classes.is_empty()).then(|| classes.join(" "))
+                },
+            ))
+        };
+        let (may_on, may_st) = st.into().split();
+        if let Some(on) = may_on {
+            self.initial_values.push(patch(on));
+        }
+        if let Some(st) = may_st {
+            self.updates.push(Box::pin(st.map(patch)));
+        }
+        self
+    }
+
+    /// Set a plain attribute for each key value pair of an iterator, in order.
+    ///
+    /// This is what the `rsx!` attribute spread `div(..attribs)` expands to.
+    /// Later pairs override earlier ones with the same key.
+    pub fn with_attribs<K, V>(self, attribs: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        attribs.into_iter().fold(self, |builder, (k, v)| {
+            builder.with_single_attrib_stream(k, v.into())
+        })
+    }
+
+    /// Add a `data-testid` attribute, for locating this view in end-to-end
+    /// tests.
+    ///
+    /// The attribute is only added when the `testids` feature is enabled,
+    /// which keeps test ids out of production markup.
+    pub fn with_test_id(self, id: impl Into<String>) -> Self {
+        #[cfg(feature = "testids")]
+        {
+            self.with_single_attrib_stream("data-testid", id.into())
+        }
+        #[cfg(not(feature = "testids"))]
+        {
+            let _ = id;
+            self
+        }
+    }
+
+    /// Tag this builder with a `data-mogwai-key` attribute.
+    ///
+    /// When hydrating, a keyed view is matched to the existing node with the
+    /// same key instead of by its index among its siblings, which keeps
+    /// hydration stable when sibling text nodes shift. Unlike an `id` the key
+    /// only has to be unique among its siblings.
+    pub fn with_key(self, key: impl Into<String>) -> Self {
+        self.with_single_attrib_stream("data-mogwai-key", key.into())
+    }
+
+    /// Set the `tabindex` of this builder, which determines whether and in
+    /// what order it can be focused with the keyboard.
+    ///
+    /// `-1` makes the view focusable only programmatically, `0` places it in
+    /// the natural focus order.
+    pub fn with_tabindex(self, i: i32) -> Self {
+        self.with_single_attrib_stream("tabindex", i.to_string())
+    }
+
+    /// Link this builder to the `description` that describes it, eg. help or
+    /// error text for an input, returning both.
+    ///
+    /// The description is given a generated [`unique_id`] (or keeps the `id`
+    /// it was given) and this builder's `aria-describedby` attribute is set to
+    /// that id. Place the returned description anywhere in the view.
+    pub fn with_aria_describedby(self, description: ViewBuilder) -> (Self, ViewBuilder) {
+        let id = description.initial_values.iter().find_map(|update| match update {
+            Update::Attribute(HashPatch::Insert(k, v)) if k == "id" => Some(v.clone()),
+            _ => None,
+        });
+        let (id, description) = match id {
+            Some(id) => (id, description),
+            None => {
+                let id = unique_id("mogwai-description");
+                (id.clone(), description.with_single_attrib_stream("id", id))
+            }
+        };
+        (self.with_single_attrib_stream("aria-describedby", id), description)
+    }
+
     /// Add a stream to patch the boolean attributes of this builder.
     pub fn with_bool_attrib_stream<St: Stream<Item = HashPatch<String, bool>> + Send + 'static>(
         mut self,
@@ -671,6 +1039,46 @@ impl ViewBuilder {
         self
     }
 
+    /// Add a stream to set or remove a single style property of this builder.
+    ///
+    /// `Some(value)` sets the property and `None` removes it from the style
+    /// entirely.
+    pub fn with_optional_style_stream<St: Stream<Item = Option<String>> + Send + 'static>(
+        mut self,
+        k: impl Into<String>,
+        st: impl Into<MogwaiValue<Option<String>, St>>,
+    ) -> Self {
+        let key = k.into();
+        let patch = move |may_val: Option<String>| match may_val {
+            Some(val) => HashPatch::Insert(key.clone(), val),
+            None => HashPatch::Remove(key.clone()),
+        };
+        let (may_val, may_st) = st.into().split();
+        if let Some(val) = may_val {
+            self.initial_values.push(Update::Style(patch(val)));
+        }
+        if let Some(st) = may_st {
+            self.updates
+                .push(Box::pin(st.map(move |v| Update::Style(patch(v)))));
+        }
+        self
+    }
+
+    /// Set a single style property of this builder from a value or stream of
+    /// either `String` or `Option<String>`.
+    ///
+    /// This is what the `style:<name>` [`rsx`](crate::rsx) attribute uses, so
+    /// `style:float = ("left", optional_floats)` removes the property whenever
+    /// the stream yields `None`. See [`ViewBuilder::with_single_style_stream`]
+    /// and [`ViewBuilder::with_optional_style_stream`].
+    pub fn with_single_style<Marker>(
+        self,
+        k: impl Into<String>,
+        value: impl SingleStyleValue<Marker>,
+    ) -> Self {
+        value.add_to_builder(self, k.into())
+    }
+
     /// Add a stream to patch the list of children of this builder.
     pub fn with_child_stream<St: Stream<Item = ListPatch<ViewBuilder>> + Send + 'static>(
         mut self,
@@ -686,24 +1094,321 @@ impl ViewBuilder {
         self
     }
 
+    /// Add a stream of patches to the children of this builder, keeping the
+    /// children in the reverse order of the patched list.
+    ///
+    /// Each patch is translated with [`ListPatch::reversed`], so indices in
+    /// the incoming patches refer to the un-reversed list. The reversal
+    /// assumes this stream is the only source of this builder's children.
+    pub fn with_reversed_child_stream<
+        St: Stream<Item = ListPatch<ViewBuilder>> + Send + 'static,
+    >(
+        mut self,
+        st: impl Into<MogwaiValue<ListPatch<ViewBuilder>, St>>,
+    ) -> Self {
+        let mut shape: Vec<()> = vec![];
+        let mut reverse = move |patch: ListPatch<ViewBuilder>| {
+            let patch = patch.reversed(shape.len());
+            shape.list_patch_apply(patch.as_ref().map(|_| ()));
+            patch
+        };
+        let (may_patch, may_st) = st.into().split();
+        if let Some(patch) = may_patch {
+            self.initial_values.push(Update::Child(reverse(patch)));
+        }
+        if let Some(st) = may_st {
+            self.updates
+                .push(Box::pin(st.map(move |patch| Update::Child(reverse(patch)))));
+        }
+        self
+    }
+
+    /// Add children for each item of the given model, keeping the children
+    /// sorted by `key` as the model is patched.
+    ///
+    /// Items with equal keys keep the order in which they were added. Each
+    /// patch of the model only inserts the views of added items and removes
+    /// the views of removed items, so the views of other items are left as
+    /// they are. The view of a replaced item is replaced and then repositioned
+    /// with [`ListPatch::Move`]. The sorting assumes the model is the only
+    /// source of this builder's children.
+    pub fn sorted_children<T, K>(
+        mut self,
+        model: &ListPatchModel<T>,
+        key: impl Fn(&T) -> K + Send + 'static,
+        view: impl Fn(&T) -> ViewBuilder + Send + 'static,
+    ) -> Self
+    where
+        T: Clone + Send + Sync + 'static,
+        K: Ord + Send + 'static,
+    {
+        let mut sorted = SortedListPatches::default();
+        // the model's stream includes the patches made before this call, so
+        // there is no need to start from the model's current items
+        let mut translate = move |patch: ListPatch<T>| {
+            sorted
+                .patch(patch, &key)
+                .into_iter()
+                .map(|patch| Update::Child(patch.map(|t| view(&t))))
+                .collect::<Vec<_>>()
+        };
+        self.updates.push(Box::pin(
+            model
+                .stream()
+                .flat_map(move |patch| futures_lite::stream::iter(translate(patch))),
+        ));
+        self
+    }
+
+    /// Add sections that are each shown as a fallback until their future
+    /// resolves to the section's view, revealing the resolved sections in the
+    /// given order.
+    ///
+    /// With [`RevealOrder::Forwards`] a resolved section is only revealed once
+    /// all the sections above it have been revealed, so sections appear top to
+    /// bottom no matter the order their futures resolve in. With
+    /// [`RevealOrder::Together`] all sections are revealed at once when the
+    /// last future resolves. This assumes the sections are the only source of
+    /// this builder's children.
+    pub fn with_suspense_list<Fut>(
+        mut self,
+        items: Vec<(ViewBuilder, Fut)>,
+        reveal_order: RevealOrder,
+    ) -> Self
+    where
+        Fut: Future<Output = ViewBuilder> + Send + 'static,
+    {
+        let len = items.len();
+        let (fallbacks, futures): (Vec<_>, Vec<_>) = items.into_iter().unzip();
+        self = self.append(fallbacks);
+
+        let mut resolved: Vec<Option<ViewBuilder>> = (0..len).map(|_| None).collect();
+        let mut revealed = 0;
+        let reveal = move |(index, view): (usize, ViewBuilder)| {
+            resolved[index] = Some(view);
+            let ready = match reveal_order {
+                RevealOrder::Forwards => resolved[revealed..]
+                    .iter()
+                    .take_while(|view| view.is_some())
+                    .count(),
+                RevealOrder::Together if resolved.iter().all(Option::is_some) => len,
+                RevealOrder::Together => 0,
+            };
+            let patches = (revealed..revealed + ready)
+                .filter_map(|i| resolved[i].take().map(|view| ListPatch::replace(i, view)))
+                .collect::<Vec<_>>();
+            revealed += ready;
+            futures_lite::stream::iter(patches.into_iter().map(Update::Child))
+        };
+        let sections = futures
+            .into_iter()
+            .enumerate()
+            .map(|(index, fut)| {
+                Box::pin(futures_lite::stream::once_future(async move { (index, fut.await) }))
+            })
+            .collect::<Vec<_>>();
+        if let Some(sections) = crate::stream::select_all(sections) {
+            self.updates.push(Box::pin(sections.flat_map(reveal)));
+        }
+        self
+    }
+
+    /// Bind this builder's child to the state of a [`StateMachine`], using
+    /// `view` to create the child for each state.
+    ///
+    /// The child is replaced each time the machine changes state. This
+    /// assumes the machine is the only source of this builder's children.
+    pub fn with_state_machine<S, E>(
+        self,
+        machine: &StateMachine<S, E>,
+        view: impl Fn(&S) -> ViewBuilder + Send + 'static,
+    ) -> Self
+    where
+        S: Clone + PartialEq + Send + Sync + 'static,
+        E: 'static,
+    {
+        self.with_child_stream(
+            machine
+                .stream()
+                .map(move |state| ListPatch::splice(.., std::iter::once(view(&state)))),
+        )
+    }
+
+    /// Add a stream of keyed lines of text to be kept as the text children of
+    /// this builder.
+    ///
+    /// Each item of the stream is the complete list of lines. Text nodes are
+    /// reused by key, so a line whose key stays in place only has its text
+    /// updated, and only when the text has changed. Lines with new keys are
+    /// inserted, lines with missing keys are removed and lines whose key moved
+    /// are re-inserted at their new position. This assumes the stream is the
+    /// only source of this builder's children.
+    pub fn with_keyed_text_children<K, St>(mut self, st: St) -> Self
+    where
+        K: PartialEq + Send + 'static,
+        St: Stream<Item = Vec<(K, String)>> + Send + 'static,
+    {
+        struct Line<K> {
+            key: K,
+            text: String,
+            tx: crate::channel::mpsc::Sender<String>,
+        }
+
+        fn new_line<K>(index: usize, key: K, text: String) -> (Line<K>, ListPatch<ViewBuilder>) {
+            let (tx, rx) = crate::channel::mpsc::unbounded::<String>();
+            let builder = ViewBuilder::text((text.clone(), rx));
+            (Line { key, text, tx }, ListPatch::insert(index, builder))
+        }
+
+        let mut lines: Vec<Line<K>> = vec![];
+        let diff = move |next: Vec<(K, String)>| {
+            let mut patches = vec![];
+            for index in (0..lines.len()).rev() {
+                if !next.iter().any(|(key, _)| key == &lines[index].key) {
+                    lines.remove(index);
+                    patches.push(ListPatch::remove(index));
+                }
+            }
+            for (index, (key, text)) in next.into_iter().enumerate() {
+                match lines.iter().position(|line| line.key == key) {
+                    Some(i) if i == index => {
+                        let line = &mut lines[index];
+                        if line.text != text {
+                            let _ = line.tx.try_send(text.clone());
+                            line.text = text;
+                        }
+                    }
+                    may_index => {
+                        if let Some(i) = may_index {
+                            lines.remove(i);
+                            patches.push(ListPatch::remove(i));
+                        }
+                        let (line, patch) = new_line(index, key, text);
+                        lines.insert(index, line);
+                        patches.push(patch);
+                    }
+                }
+            }
+            futures_lite::stream::iter(patches.into_iter().map(Update::Child))
+        };
+        self.updates.push(Box::pin(st.flat_map(diff)));
+        self
+    }
+
     /// Append a child or iterator of children.
+    ///
+    /// Anything that converts into a [`ViewBuilder`] can be appended, as well
+    /// as an `Option`, `Vec` or slice of such things. `None` appends nothing.
     pub fn append(mut self, children: impl Into<AppendArg>) -> Self {
         let arg = children.into();
 
         match arg {
-            AppendArg::Single(bldr) => {
-                self.initial_values
-                    .push(Update::Child(ListPatch::push(bldr)));
-            }
+            AppendArg::Single(bldr) => self.push_child(bldr),
             AppendArg::Iter(bldrs) => {
-                self.initial_values
-                    .extend(bldrs.into_iter().map(|b| Update::Child(ListPatch::push(b))));
+                for bldr in bldrs.into_iter() {
+                    self.push_child(bldr);
+                }
             }
         }
 
         self
     }
 
+    /// Push a child, appending the children of a fragment in its place.
+    fn push_child(&mut self, child: ViewBuilder) {
+        if child.is_fragment() {
+            let ViewBuilder {
+                initial_values,
+                updates,
+                post_build_ops,
+                view_sinks,
+                listeners,
+                tasks,
+                ..
+            } = child;
+            let (children, values): (Vec<_>, Vec<_>) = initial_values
+                .into_iter()
+                .partition(|update| matches!(update, Update::Child(_)));
+            if !(values.is_empty()
+                && updates.is_empty()
+                && post_build_ops.is_empty()
+                && view_sinks.is_empty()
+                && listeners.is_empty())
+            {
+                log::warn!(
+                    "a fragment's text, attributes, styles, updates, post-build ops, captures \
+                     and listeners are dropped when it is appended, add them to its parent or \
+                     children instead"
+                );
+            }
+            self.initial_values.extend(children);
+            self.tasks.extend(tasks);
+        } else {
+            self.initial_values
+                .push(Update::Child(ListPatch::push(child)));
+        }
+    }
+
+    /// Append each child of an iterator, in order.
+    ///
+    /// Unlike [`ViewBuilder::append`] this does not collect the children into
+    /// an intermediate `Vec` first, and space for the children is reserved up
+    /// front using the iterator's size hint.
+    pub fn append_iter<I: IntoIterator<Item = ViewBuilder>>(mut self, children: I) -> Self {
+        let children = children.into_iter();
+        self.initial_values.reserve(children.size_hint().0);
+        for child in children {
+            self.push_child(child);
+        }
+        self
+    }
+
+    /// Append a child only if `cond` is true, otherwise return the builder
+    /// unchanged.
+    ///
+    /// This reads better than appending an `Option` built just for the
+    /// condition.
+    pub fn with_child_if(self, cond: bool, child: impl Into<ViewBuilder>) -> Self {
+        if cond {
+            self.append(child.into())
+        } else {
+            self
+        }
+    }
+
+    /// Append the child built by `f`, or show its error if building fails.
+    ///
+    /// The error is logged and the rest of the view is built as usual. In
+    /// debug builds the error message is shown inline in a red box in place
+    /// of the child, while in release builds nothing is shown.
+    pub fn with_fallible_child(self, f: impl FnOnce() -> anyhow::Result<ViewBuilder>) -> Self {
+        match f() {
+            Ok(child) => self.append(child),
+            Err(e) => {
+                log::error!("could not build child: {:#}", e);
+                if cfg!(debug_assertions) {
+                    self.append(
+                        ViewBuilder::element("pre")
+                            .with_style_stream("color: red; border: 1px solid red")
+                            .append(format!("{:#}", e)),
+                    )
+                } else {
+                    self
+                }
+            }
+        }
+    }
+
+    /// Append an iterator of children in reverse order.
+    pub fn append_reversed<T>(self, children: impl IntoIterator<Item = T>) -> Self
+    where
+        ViewBuilder: From<T>,
+    {
+        let mut children = children.into_iter().collect::<Vec<_>>();
+        children.reverse();
+        self.append(children)
+    }
+
     /// Add an operation to perform after the view has been built.
     pub fn with_post_build<V, F>(mut self, f: F) -> Self
     where
@@ -726,6 +1431,30 @@ impl ViewBuilder {
         self
     }
 
+    /// Add an asynchronous operation to perform after the view has been built.
+    ///
+    /// Unlike [`ViewBuilder::with_task`] the future is given a clone of the
+    /// built view, so the node is guaranteed to exist. If the future returns
+    /// an error it is logged.
+    ///
+    /// ## Panics
+    /// Panics if the `AnyView` cannot be downcast back into `V`.
+    pub fn with_post_build_async<V, F, Fut>(self, f: F) -> Self
+    where
+        V: View,
+        AnyView: Downcast<V>,
+        F: FnOnce(V) -> Fut + Send + 'static,
+        Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+    {
+        let captured = crate::future::Captured::<V>::default();
+        self.with_capture_view(captured.sink()).with_task(async move {
+            let view = captured.get().await;
+            if let Err(e) = f(view).await {
+                log::error!("async post build failed: {:#}", e);
+            }
+        })
+    }
+
     /// Send a clone of the inner view once it is built.
     ///
     /// Wraps `V` in `AnyView` to erase its type until it is built.
@@ -780,6 +1509,9 @@ impl ViewBuilder {
     ///         }
     ///     })
     /// ```
+    ///
+    /// In `mogwai-dom`, `JsDom::capture_for_each` builds this tuple from a
+    /// stream and a typed `web_sys` setter.
     pub fn with_capture_for_each<T, V: View>(
         self,
         (mut st, f): (
@@ -800,6 +1532,37 @@ impl ViewBuilder {
             })
     }
 
+    /// Run an asynchronous task exactly once, the first time an event of the
+    /// given name occurs on the view.
+    ///
+    /// The task is given a clone of the built view. Any later events of the
+    /// same name are ignored.
+    ///
+    /// ## Panics
+    /// Panics if the `AnyView` cannot be downcast back into `V`.
+    pub fn with_task_on_first<V: View, Fut>(
+        self,
+        name: &'static str,
+        f: impl FnOnce(V) -> Fut + Send + 'static,
+    ) -> Self
+    where
+        AnyView: Downcast<V>,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let captured = crate::future::Captured::<V>::default();
+        let (tx, mut rx) = crate::channel::mpsc::unbounded::<AnyEvent>();
+        self.with_capture_view(captured.sink())
+            .with_event(name, "myself", tx)
+            .with_task(async move {
+                if rx.next().await.is_some() {
+                    // close the channel so later events are dropped
+                    drop(rx);
+                    let view = captured.get().await;
+                    f(view).await;
+                }
+            })
+    }
+
     /// Add a sink into which view events of the given name will be sent.
     ///
     /// ## Panics
@@ -859,6 +1622,16 @@ where
     }
 }
 
+impl<T> From<&[T]> for AppendArg
+where
+    T: Clone,
+    ViewBuilder: From<T>,
+{
+    fn from(bldrs: &[T]) -> Self {
+        AppendArg::Iter(bldrs.iter().cloned().map(ViewBuilder::from).collect())
+    }
+}
+
 impl From<&String> for ViewBuilder {
     fn from(s: &String) -> Self {
         ViewBuilder::text(s)
@@ -10,15 +10,12 @@ use wasm_bindgen::prelude::*;
 #[global_allocator]
 static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
 
-#[wasm_bindgen]
-pub fn main(parent_id: Option<String>) -> Result<(), JsValue> {
-    panic::set_hook(Box::new(console_error_panic_hook::hook));
-    console_log::init_with_level(Level::Trace).unwrap();
-
+/// A button that counts how many times it has been clicked.
+pub fn counter() -> ViewBuilder {
     let clicked = Output::<()>::default();
     let mut message = Input::<String>::default();
 
-    let bldr: ViewBuilder = rsx! {
+    rsx! {
         button(
             style:cursor = "pointer",
             on:click = clicked.sink().contra_map(|_: JsDomEvent| ())
@@ -43,9 +40,15 @@ pub fn main(parent_id: Option<String>) -> Result<(), JsValue> {
                 None => break,
             }
         }
-    });
+    })
+}
 
-    let view = JsDom::try_from(bldr).unwrap();
+#[wasm_bindgen]
+pub fn main(parent_id: Option<String>) -> Result<(), JsValue> {
+    panic::set_hook(Box::new(console_error_panic_hook::hook));
+    console_log::init_with_level(Level::Trace).unwrap();
+
+    let view = JsDom::try_from(counter()).unwrap();
     if let Some(id) = parent_id {
         let doc = mogwai_dom::utils::document();
         let parent = doc
@@ -61,3 +64,16 @@ pub fn main(parent_id: Option<String>) -> Result<(), JsValue> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn counter_snapshot() {
+        mogwai_dom::assert_view_snapshot!(
+            counter(),
+            r#"<button style="cursor: pointer;">Click me!</button>"#
+        );
+    }
+}
@@ -10,12 +10,11 @@ use wasm_bindgen::prelude::*;
 #[global_allocator]
 static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
 
-/// Create an SVG circle using the xmlns attribute and the SVG namespace.
+/// Create an SVG circle in the SVG namespace.
 fn my_circle() -> ViewBuilder {
-    let ns = "http://www.w3.org/2000/svg";
     html! {
-        <svg xmlns=ns width="100" height="100">
-            <circle xmlns=ns
+        <svg xmlns:svg width="100" height="100">
+            <circle
                 cx="50"
                 cy="50"
                 r="40"